@@ -1,28 +1,35 @@
 mod action;
+mod bode_window;
 mod control_panel;
 mod elements_panel;
 mod field;
+mod scope_panel;
 
 use std::collections::HashSet;
 
-use egui::Key;
 use egui::{emath::TSTransform, InputState, LayerId, PointerButton, Pos2};
+use egui::{Align2, Color32, Id, Key};
 
-use circuit::{circuit::ElementId, Circuit};
+use circuit::circuit::{ElementId, Probe, SolveError};
+use circuit::Circuit;
 
-use crate::element::{Element, ElementPos};
+use crate::element::{Element, ElementPos, Properties};
 use crate::utils::Painter;
 use action::Action;
+use bode_window::BodeWindow;
 use control_panel::ControlPanel;
 
 use elements_panel::ElementsPanel;
 use field::Field;
+use scope_panel::ScopePanel;
 
 #[derive(Default)]
 pub struct App<'data> {
     field: Field,
     elements_panel: ElementsPanel,
     control_panel: ControlPanel,
+    scope_panel: ScopePanel,
+    bode_window: BodeWindow,
 
     state: AppState<'data>,
     action: Action,
@@ -42,10 +49,28 @@ impl<'data> eframe::App for App<'data> {
         self.field.show(&mut self.state, ctx, &mut self.action);
         self.elements_panel.show(ctx, &mut self.action);
         self.control_panel.show(&mut self.state, ctx);
+        self.scope_panel.show(&mut self.state, ctx);
+        self.bode_window.show(&mut self.state, ctx);
 
         self.action.update(ctx);
         self.action.apply(&mut self.state);
         self.action.draw(ctx, painter);
+
+        self.show_error(ctx);
+    }
+}
+
+impl<'data> App<'data> {
+    fn show_error(&self, ctx: Context) {
+        let Some(error) = &self.state.error else {
+            return;
+        };
+
+        egui::Area::new(Id::new("error"))
+            .anchor(Align2::CENTER_TOP, [0.0, 10.0])
+            .show(ctx.0, |ui| {
+                ui.colored_label(Color32::RED, error.to_string());
+            });
     }
 }
 
@@ -57,17 +82,33 @@ pub struct AppState<'data> {
     pub settings: Option<ElementId>,
     pub selected: HashSet<ElementId>,
     pub hovered: Option<Hovered>,
+    pub hovered_ground: Option<ElementPos>,
+    pub error: Option<SolveError>,
 }
 
 impl<'data> AppState<'data> {
     pub fn update(&mut self, ctx: Context) {
-        self.circuit.update(ctx.delta_time());
+        self.error = self.circuit.try_update(ctx.delta_time()).err();
 
         self.update_zoom(ctx);
         self.update_selected(ctx);
         self.update_settings(ctx);
     }
 
+    /// Positions of all the pins sorted by coordinates.
+    pub fn nodes(&self) -> Vec<ElementPos> {
+        let mut nodes: Vec<_> = self
+            .circuit
+            .iter()
+            .flat_map(|(id, _)| self.circuit.terminals(id))
+            .collect();
+
+        nodes.sort_by_key(|node| (node.x, node.y));
+        nodes.dedup();
+
+        nodes
+    }
+
     fn update_zoom(&mut self, ctx: Context) {
         if let Some(real_mouse_pos) = ctx.0.input(|state| state.pointer.hover_pos()) {
             let delta_scale = ctx.0.input(|state| state.zoom_delta());
@@ -86,8 +127,32 @@ impl<'data> AppState<'data> {
 
     fn update_selected(&mut self, ctx: Context) {
         if ctx.key_down(Key::Delete) {
+            // controlled sources lose the elements controlling them before they're removed
+            let ids: Vec<_> = self.circuit.iter().map(|(id, _)| id).collect();
+
+            for id in ids {
+                if let Some(control) = self.circuit.get_mut(id).control_mut() {
+                    if control.is_some_and(|control| self.selected.contains(&control)) {
+                        *control = None;
+                    }
+                }
+            }
+
             for &id in &self.selected {
                 self.circuit.remove(id);
+
+                let recorder = self.circuit.recorder_mut();
+
+                recorder.remove_probe(Probe::Current(id));
+                recorder.remove_probe(Probe::Power(id));
+            }
+
+            if let Some(ground) = self.hovered_ground.take() {
+                self.circuit.remove_ground(ground);
+            }
+
+            if self.settings.is_some_and(|id| self.selected.contains(&id)) {
+                self.settings = None;
             }
 
             self.selected.clear();
@@ -22,8 +22,7 @@ pub enum Action {
 
     Adding {
         ty: ElementType,
-        first: Option<Pos2>,
-        second: Option<Pos2>,
+        pins: Vec<Pos2>,
     },
 
     Selection {
@@ -52,7 +51,7 @@ impl Action {
                 }
             }
 
-            Action::Adding { first, second, .. } => {
+            Action::Adding { pins, .. } => {
                 let Some(clickpoint) = ctx.primary_clicked() else {
                     return;
                 };
@@ -61,11 +60,7 @@ impl Action {
                     return;
                 }
 
-                if first.is_none() {
-                    *first = Some(clickpoint);
-                } else {
-                    *second = Some(clickpoint);
-                }
+                pins.push(clickpoint);
             }
 
             Action::Selection { mouse_pos, .. } => {
@@ -96,11 +91,13 @@ impl Action {
             } => {
                 // TODO: add check for overlapping existing endpoints
 
-                for (&element, &origin_endpoints) in origin_endpoints {
-                    let new_endpoints =
-                        origin_endpoints.map(|point| point + delta / state.transform.scaling);
+                for (&element, origin_pins) in origin_endpoints {
+                    let new_pins: Vec<_> = origin_pins
+                        .iter()
+                        .map(|&point| point + delta / state.transform.scaling)
+                        .collect();
 
-                    state.circuit.change(element, new_endpoints);
+                    state.circuit.change_multi(element, &new_pins);
                 }
             }
 
@@ -116,36 +113,101 @@ impl Action {
             } => {
                 // TODO: add check for overlapping existing endpoints
 
-                let mut new_endpoints = state.circuit.endpoints(id);
+                let mut new_pins = state.circuit.terminals(id);
 
-                new_endpoints[endpoint] = origin_pos + delta / state.transform.scaling;
+                new_pins[endpoint] = origin_pos + delta / state.transform.scaling;
 
-                state.circuit.change(id, new_endpoints);
+                state.circuit.change_multi(id, &new_pins);
             }
 
-            Action::Adding { ty, first, second } => {
-                if let Some(endpoints) =
-                    first.and_then(|first| second.map(|second| [first, second]))
-                {
+            Action::Adding { ty, ref pins } => {
+                if pins.len() == ty.pins() {
                     // TODO: add check for overlapping existing endpoints
 
-                    let endpoints =
-                        endpoints.map(|pos| ElementPos::from_pos(state.transform.inverse() * pos));
+                    let pins: Vec<_> = pins
+                        .iter()
+                        .map(|&pos| ElementPos::from_pos(state.transform.inverse() * pos))
+                        .collect();
 
                     let conductor = match ty {
                         ElementType::CurrentSource => {
-                            Box::new(CurrentSource::new(10.0, 0.0)) as Box<dyn ElementTrait>
+                            Box::new(CurrentSource::<f32>::new(10.0, 0.0)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::FunctionSource => Box::new(FunctionSource::<f32>::new(
+                            Waveform::Sine {
+                                amplitude: 10.0,
+                                frequency: 1.0,
+                                phase: 0.0,
+                                offset: 0.0,
+                            },
+                            0.0,
+                        ))
+                            as Box<dyn ElementTrait>,
+                        ElementType::IdealCurrentSource => {
+                            Box::new(IdealCurrentSource::<f32>::new(1.0)) as Box<dyn ElementTrait>
                         }
                         ElementType::Resistor => {
-                            Box::new(Resistor::new(5.0)) as Box<dyn ElementTrait>
+                            Box::new(Resistor::<f32>::new(5.0)) as Box<dyn ElementTrait>
                         }
                         ElementType::Wire => Box::new(Wire) as Box<dyn ElementTrait>,
+                        // grounds aren't elements, they join their nodes into one net
+                        ElementType::Ground => {
+                            state.circuit.set_ground(pins[0]);
+
+                            *self = Self::None;
+
+                            return;
+                        }
+                        ElementType::Capacitor => {
+                            Box::new(Capacitor::<f32>::new(0.1)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::Inductor => {
+                            Box::new(Inductor::<f32>::new(1.0)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::Diode => {
+                            Box::new(Diode::<f32>::new(1e-14, 1.0)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::Led => {
+                            Box::new(Led::<f32>::new(2.0, [255, 40, 40])) as Box<dyn ElementTrait>
+                        }
+                        ElementType::ZenerDiode => {
+                            Box::new(ZenerDiode::<f32>::new(1e-14, 1.0, 3.3))
+                                as Box<dyn ElementTrait>
+                        }
+                        ElementType::Vcvs => {
+                            Box::new(Vcvs::<f32>::new(2.0)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::Vccs => {
+                            Box::new(Vccs::<f32>::new(0.1)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::Ccvs => {
+                            Box::new(Ccvs::<f32>::new(1.0, None)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::Cccs => {
+                            Box::new(Cccs::<f32>::new(2.0, None)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::IdealOpAmp => Box::new(IdealOpAmp) as Box<dyn ElementTrait>,
+                        ElementType::OpAmp => {
+                            Box::new(OpAmp::<f32>::new(1e5, 15.0, -15.0)) as Box<dyn ElementTrait>
+                        }
+                        ElementType::Npn => Box::new(Bjt::<f32>::new(Polarity::N, 100.0, 1e-14))
+                            as Box<dyn ElementTrait>,
+                        ElementType::Pnp => Box::new(Bjt::<f32>::new(Polarity::P, 100.0, 1e-14))
+                            as Box<dyn ElementTrait>,
+                        ElementType::Nmos => Box::new(Mosfet::<f32>::new(Polarity::N, 1.0, 1e-3))
+                            as Box<dyn ElementTrait>,
+                        ElementType::Pmos => Box::new(Mosfet::<f32>::new(Polarity::P, 1.0, 1e-3))
+                            as Box<dyn ElementTrait>,
                     };
 
-                    state.circuit.add(endpoints, Element::new(conductor));
-                }
+                    if ty.stamped() {
+                        state.circuit.add_multi(&pins, Element::new(conductor));
+                    } else {
+                        state
+                            .circuit
+                            .add([pins[0], pins[1]], Element::new(conductor));
+                    }
 
-                if first.is_some() && second.is_some() {
                     *self = Self::None
                 }
             }
@@ -154,9 +216,9 @@ impl Action {
                 let rect = state.transform.inverse() * Rect::from_two_pos(origin, mouse_pos);
 
                 for (id, _) in state.circuit.iter() {
-                    let endpoints = state.circuit.endpoints(id).map(ElementPos::to_pos);
+                    let pins = state.circuit.terminals(id);
 
-                    if rect.contains(endpoints[0]) && rect.contains(endpoints[1]) {
+                    if pins.into_iter().all(|pin| rect.contains(pin.to_pos())) {
                         state.selected.insert(id);
                     } else {
                         state.selected.remove(&id);
@@ -170,22 +232,81 @@ impl Action {
 
     pub fn draw(&self, ctx: Context, painter: Painter) {
         match *self {
-            Action::Adding { ty, first, second } => {
-                if let (Some(mouse_pos), Some(first), None) = (ctx.mouse_pos(), first, second) {
-                    let endpoints = [first, mouse_pos]
-                        .map(|point| painter.transform.inverse() * point)
-                        .map(ElementPos::from_pos);
+            Action::Adding { ty, ref pins } => {
+                if let Some(mouse_pos) = ctx.mouse_pos().filter(|_| !pins.is_empty()) {
+                    let pins: Vec<_> = pins
+                        .iter()
+                        .copied()
+                        .chain([mouse_pos])
+                        .map(|point| ElementPos::from_pos(painter.transform.inverse() * point))
+                        .collect();
+
+                    let endpoints = [pins[0], pins[1]];
 
                     match ty {
                         ElementType::CurrentSource => {
                             render_current_source(endpoints, painter, Color32::DARK_GRAY);
                         }
+                        ElementType::FunctionSource => {
+                            render_function_source(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::IdealCurrentSource => {
+                            render_ideal_current_source(endpoints, painter, Color32::DARK_GRAY);
+                        }
                         ElementType::Wire => {
                             render_wire(endpoints, painter, Color32::DARK_GRAY);
                         }
+                        ElementType::Ground => {
+                            render_ground(pins[0], painter, Color32::DARK_GRAY);
+                        }
                         ElementType::Resistor => {
                             render_resistor(endpoints, painter, Color32::DARK_GRAY);
                         }
+                        ElementType::Capacitor => {
+                            render_capacitor(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Inductor => {
+                            render_inductor(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Diode => {
+                            render_diode(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Led => {
+                            render_led(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::ZenerDiode => {
+                            render_zener_diode(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Vcvs => {
+                            render_vcvs(&pins, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Vccs => {
+                            render_vccs(&pins, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Ccvs => {
+                            render_ccvs(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Cccs => {
+                            render_cccs(endpoints, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::IdealOpAmp => {
+                            render_ideal_op_amp(&pins, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::OpAmp => {
+                            render_op_amp(&pins, painter, Color32::DARK_GRAY);
+                        }
+                        ElementType::Npn => {
+                            render_bjt(&pins, painter, Color32::DARK_GRAY, Polarity::N);
+                        }
+                        ElementType::Pnp => {
+                            render_bjt(&pins, painter, Color32::DARK_GRAY, Polarity::P);
+                        }
+                        ElementType::Nmos => {
+                            render_mosfet(&pins, painter, Color32::DARK_GRAY, Polarity::N);
+                        }
+                        ElementType::Pmos => {
+                            render_mosfet(&pins, painter, Color32::DARK_GRAY, Polarity::P);
+                        }
                     }
                 }
             }
@@ -222,7 +343,7 @@ pub enum MovingObject {
         origin_translation: Vec2,
     },
     Elements {
-        origin_endpoints: HashMap<ElementId, [ElementPos; 2]>,
+        origin_endpoints: HashMap<ElementId, Vec<ElementPos>>,
     },
     Endpoint {
         origin_pos: ElementPos,
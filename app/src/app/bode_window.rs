@@ -0,0 +1,192 @@
+use egui::{Align2, Color32, ComboBox, FontId, Pos2, Rounding, Sense, Shape, Stroke, Vec2};
+use parse_int::parse;
+
+use circuit::circuit::{BodePoint, ElementId, SolveError, SweepScale};
+
+use super::control_panel::node_name;
+use super::{AppState, Context};
+use crate::element::{ElementPos, HIGHLIGHTED_COLOR};
+
+const PLOT_SIZE: Vec2 = Vec2::new(320.0, 120.0);
+
+/// Gain and phase of the voltage of a node driven by a source over a range of frequencies.
+pub struct BodeWindow {
+    input: Option<ElementId>,
+    output: Option<ElementPos>,
+    start: f32,
+    stop: f32,
+    points: usize,
+    scale: SweepScale,
+    result: Result<Vec<BodePoint>, SolveError>,
+}
+
+impl Default for BodeWindow {
+    fn default() -> Self {
+        Self {
+            input: None,
+            output: None,
+            start: 1.0,
+            stop: 100_000.0,
+            points: 100,
+            scale: SweepScale::Log,
+            result: Ok(vec![]),
+        }
+    }
+}
+
+impl BodeWindow {
+    pub fn show(&mut self, state: &mut AppState, ctx: Context) {
+        let mut ids: Vec<_> = state.circuit.iter().map(|(id, _)| id).collect();
+        ids.sort();
+
+        let nodes = state.nodes();
+
+        egui::Window::new("bode plot")
+            .default_open(false)
+            .show(ctx.0, |ui| {
+                ui.label("input");
+
+                ComboBox::from_id_source("bode input")
+                    .selected_text(self.input.map_or("none".to_owned(), |id| format!("{id:?}")))
+                    .show_ui(ui, |ui| {
+                        for &id in &ids {
+                            ui.selectable_value(&mut self.input, Some(id), format!("{id:?}"));
+                        }
+                    });
+
+                ui.label("output");
+
+                ComboBox::from_id_source("bode output")
+                    .selected_text(self.output.map_or("none".to_owned(), node_name))
+                    .show_ui(ui, |ui| {
+                        for &node in &nodes {
+                            ui.selectable_value(&mut self.output, Some(node), node_name(node));
+                        }
+                    });
+
+                for (name, value_ref) in
+                    [("start, Hz", &mut self.start), ("stop, Hz", &mut self.stop)]
+                {
+                    let mut value = value_ref.to_string();
+
+                    ui.label(name);
+
+                    if ui.text_edit_singleline(&mut value).changed() {
+                        if let Ok(value) = parse(&value) {
+                            *value_ref = value
+                        }
+                    }
+                }
+
+                let mut points = self.points.to_string();
+
+                ui.label("points");
+
+                if ui.text_edit_singleline(&mut points).changed() {
+                    if let Ok(points) = parse(&points) {
+                        self.points = points
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.scale, SweepScale::Log, "log");
+                    ui.selectable_value(&mut self.scale, SweepScale::Linear, "linear");
+
+                    if ui.button("sweep").clicked() {
+                        self.result = match (self.input, self.output) {
+                            (Some(input), Some(output)) => state.circuit.try_frequency_sweep(
+                                self.start..=self.stop,
+                                self.points,
+                                self.scale,
+                                input,
+                                output,
+                            ),
+                            _ => Ok(vec![]),
+                        };
+                    }
+                });
+
+                match &self.result {
+                    Ok(points) if !points.is_empty() => {
+                        plot(ui, "gain, dB", points, |point| point.gain);
+                        plot(ui, "phase, °", points, |point| point.phase);
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        ui.colored_label(Color32::RED, error.to_string());
+                    }
+                }
+            });
+    }
+}
+
+/// The points are evenly spaced like the frequencies in the scale of the sweep,
+/// the hovered one is shown with its frequency.
+fn plot(ui: &mut egui::Ui, name: &str, points: &[BodePoint], value: impl Fn(&BodePoint) -> f32) {
+    // the gain is infinite at zeros of the transfer function, the plot skips such points
+    let finite = || points.iter().map(&value).filter(|value| value.is_finite());
+
+    let min = finite().fold(f32::INFINITY, f32::min);
+    let max = finite().fold(f32::NEG_INFINITY, f32::max);
+
+    ui.label(format!("{name}: {min:.1} … {max:.1}"));
+
+    let (response, painter) = ui.allocate_painter(PLOT_SIZE, Sense::hover());
+    let rect = response.rect;
+
+    painter.rect_stroke(rect, Rounding::ZERO, Stroke::new(1.0, Color32::DARK_GRAY));
+
+    let intervals = points.len().saturating_sub(1).max(1) as f32;
+    let range = (max - min).max(f32::EPSILON);
+
+    let position = |idx: usize| {
+        let y = (value(&points[idx]) - min) / range;
+
+        Pos2::new(
+            rect.left() + rect.width() * idx as f32 / intervals,
+            rect.bottom() - rect.height() * y,
+        )
+    };
+
+    let indices: Vec<_> = (0..points.len()).collect();
+
+    for run in indices.split(|&idx| !value(&points[idx]).is_finite()) {
+        let line = run.iter().map(|&idx| position(idx)).collect();
+
+        painter.add(Shape::line(line, Stroke::new(1.5, HIGHLIGHTED_COLOR)));
+    }
+
+    // the nearest point with a finite value is hovered
+    let hovered = response.hover_pos().and_then(|hover| {
+        let hovered = (hover.x - rect.left()) / rect.width() * intervals;
+        let distance = |idx: usize| (idx as f32 - hovered).abs();
+
+        (0..points.len())
+            .filter(|&idx| value(&points[idx]).is_finite())
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+    });
+
+    if let Some(idx) = hovered {
+        let position = position(idx);
+
+        painter.line_segment(
+            [
+                Pos2::new(position.x, rect.top()),
+                Pos2::new(position.x, rect.bottom()),
+            ],
+            Stroke::new(1.0, Color32::GRAY),
+        );
+
+        painter.text(
+            rect.left_top() + Vec2::splat(4.0),
+            Align2::LEFT_TOP,
+            format!(
+                "{:.1} Hz: {:.2}",
+                points[idx].frequency,
+                value(&points[idx])
+            ),
+            FontId::monospace(12.0),
+            Color32::GRAY,
+        );
+    }
+}
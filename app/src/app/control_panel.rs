@@ -1,20 +1,58 @@
+use egui::{ComboBox, DragValue};
 use parse_int::parse;
 
+use circuit::circuit::Probe;
+use circuit::default_conductors::Waveform;
+
+use super::scope_panel::channel_name;
 use super::{AppState, Context};
-use crate::element::Properties;
+use crate::element::{ElementPos, Properties};
 
 #[derive(Default)]
 pub struct ControlPanel;
 
 impl ControlPanel {
     pub fn show(&mut self, state: &mut AppState, ctx: Context) {
-        let Some(element) = state.settings else {
+        let Some(id) = state.settings else {
+            return;
+        };
+
+        // the element may have been removed since it was opened
+        let Ok(mut pins) = state.circuit.try_terminals(id) else {
+            state.settings = None;
             return;
         };
 
-        let element = state.circuit.get_mut(element);
+        let mut others: Vec<_> = state
+            .circuit
+            .iter()
+            .map(|(other, _)| other)
+            .filter(|&other| other != id)
+            .collect();
+
+        others.sort();
+
+        let nodes = state.nodes();
 
         egui::SidePanel::left("control").show(ctx.0, |ui| {
+            let element = state.circuit.get_mut(id);
+
+            if let Some(waveform) = element.waveform_mut() {
+                ui.label("waveform");
+
+                ComboBox::from_id_source("waveform")
+                    .selected_text(waveform_name(waveform))
+                    .show_ui(ui, |ui| {
+                        for (name, default) in waveforms() {
+                            let selected = waveform_name(waveform) == name;
+
+                            if ui.selectable_label(selected, name).clicked() && !selected {
+                                *waveform = default;
+                            }
+                        }
+                    });
+            }
+
             let names = element.properties().iter();
             let values = element.properties_mut().into_iter();
 
@@ -30,6 +68,164 @@ impl ControlPanel {
                     }
                 }
             }
+
+            if let Some(Waveform::PiecewiseLinear(points)) = element.waveform_mut() {
+                ui.label("points");
+
+                let mut removed = None;
+
+                // times stay sorted by keeping each point between its neighbours
+                for i in 0..points.len() {
+                    let min = i.checked_sub(1).map_or(0.0, |prev| points[prev].0);
+                    let max = points.get(i + 1).map_or(f32::INFINITY, |next| next.0);
+
+                    let (time, value) = &mut points[i];
+
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(time).speed(0.01).range(min..=max));
+                        ui.add(DragValue::new(value).speed(0.1));
+
+                        if ui.button("remove").clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                }
+
+                if let Some(i) = removed {
+                    points.remove(i);
+                }
+
+                if ui.button("add point").clicked() {
+                    let (time, value) = points
+                        .last()
+                        .map_or((0.0, 0.0), |&(time, value)| (time + 1.0, value));
+
+                    points.push((time, value));
+                }
+            }
+
+            if let Some(control) = element.control_mut() {
+                ui.label("control");
+
+                ComboBox::from_id_source("control")
+                    .selected_text(control.map_or("none".to_owned(), |id| format!("{id:?}")))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(control, None, "none");
+
+                        for &other in &others {
+                            ui.selectable_value(control, Some(other), format!("{other:?}"));
+                        }
+                    });
+            }
+
+            if let Some(control_pins) = element.control_pins() {
+                let mut changed = false;
+
+                for (name, pin) in
+                    Iterator::zip(["control +", "control -"].into_iter(), control_pins)
+                {
+                    ui.label(name);
+
+                    ComboBox::from_id_source(name)
+                        .selected_text(node_name(pins[pin]))
+                        .show_ui(ui, |ui| {
+                            for &node in &nodes {
+                                changed |= ui
+                                    .selectable_value(&mut pins[pin], node, node_name(node))
+                                    .changed();
+                            }
+                        });
+                }
+
+                if changed {
+                    state.circuit.change_multi(id, &pins);
+                }
+            }
+
+            ui.label("scope");
+
+            let recorder = state.circuit.recorder_mut();
+
+            let probes = [Probe::Current(id), Probe::Power(id)]
+                .into_iter()
+                .chain(pins.iter().map(|&pin| Probe::Voltage(pin)));
+
+            ComboBox::from_id_source("scope")
+                .selected_text(format!("{} channels", recorder.probes().len()))
+                .show_ui(ui, |ui| {
+                    for probe in probes {
+                        let recorded = recorder.probes().contains(&probe);
+
+                        if ui.selectable_label(recorded, channel_name(probe)).clicked() {
+                            if recorded {
+                                recorder.remove_probe(probe);
+                            } else {
+                                recorder.add_probe(probe);
+                            }
+                        }
+                    }
+                });
         });
     }
 }
+
+fn waveform_name(waveform: &Waveform) -> &'static str {
+    match waveform {
+        Waveform::Sine { .. } => "sine",
+        Waveform::Square { .. } => "square",
+        Waveform::Triangle { .. } => "triangle",
+        Waveform::Pulse { .. } => "pulse",
+        Waveform::PiecewiseLinear(_) => "piecewise linear",
+    }
+}
+
+fn waveforms() -> [(&'static str, Waveform); 5] {
+    [
+        (
+            "sine",
+            Waveform::Sine {
+                amplitude: 10.0,
+                frequency: 1.0,
+                phase: 0.0,
+                offset: 0.0,
+            },
+        ),
+        (
+            "square",
+            Waveform::Square {
+                amplitude: 10.0,
+                frequency: 1.0,
+                duty_cycle: 0.5,
+                offset: 0.0,
+            },
+        ),
+        (
+            "triangle",
+            Waveform::Triangle {
+                amplitude: 10.0,
+                frequency: 1.0,
+                offset: 0.0,
+            },
+        ),
+        (
+            "pulse",
+            Waveform::Pulse {
+                low: 0.0,
+                high: 10.0,
+                delay: 0.0,
+                rise: 0.1,
+                width: 0.5,
+                fall: 0.1,
+                period: 2.0,
+            },
+        ),
+        (
+            "piecewise linear",
+            Waveform::PiecewiseLinear(vec![(0.0, 0.0), (1.0, 10.0), (2.0, 0.0)]),
+        ),
+    ]
+}
+
+pub(super) fn node_name(node: ElementPos) -> String {
+    format!("({}, {})", node.x, node.y)
+}
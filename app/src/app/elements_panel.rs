@@ -48,22 +48,40 @@ impl ElementsPanel {
         }
 
         if let Some(ty) = adding.get() {
-            action.try_init(|| Action::Adding {
-                ty,
-                first: None,
-                second: None,
-            });
+            action.try_init(|| Action::Adding { ty, pins: vec![] });
         }
     }
 
     fn buttons<'frame>(
         &'frame mut self,
         adding_ty: &'frame Cell<Option<ElementType>>,
-    ) -> impl Iterator<Item = (Button, Box<dyn FnOnce(egui::Response) + '_>)> + '_ {
+    ) -> impl Iterator<Item = (Button<'frame>, Box<dyn FnOnce(egui::Response) + 'frame>)> + 'frame
+    {
         let buttons = [
             (Button::new("wire"), ElementType::Wire),
+            (Button::new("ground"), ElementType::Ground),
             (Button::new("resistor"), ElementType::Resistor),
+            (Button::new("capacitor"), ElementType::Capacitor),
+            (Button::new("inductor"), ElementType::Inductor),
+            (Button::new("diode"), ElementType::Diode),
+            (Button::new("led"), ElementType::Led),
+            (Button::new("zener diode"), ElementType::ZenerDiode),
             (Button::new("current source"), ElementType::CurrentSource),
+            (Button::new("function source"), ElementType::FunctionSource),
+            (
+                Button::new("ideal current source"),
+                ElementType::IdealCurrentSource,
+            ),
+            (Button::new("vcvs"), ElementType::Vcvs),
+            (Button::new("vccs"), ElementType::Vccs),
+            (Button::new("ccvs"), ElementType::Ccvs),
+            (Button::new("cccs"), ElementType::Cccs),
+            (Button::new("ideal op-amp"), ElementType::IdealOpAmp),
+            (Button::new("op-amp"), ElementType::OpAmp),
+            (Button::new("npn"), ElementType::Npn),
+            (Button::new("pnp"), ElementType::Pnp),
+            (Button::new("nmos"), ElementType::Nmos),
+            (Button::new("pmos"), ElementType::Pmos),
         ]
         .into_iter();
 
@@ -81,7 +99,47 @@ impl ElementsPanel {
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ElementType {
+    Capacitor,
+    Inductor,
+    Diode,
+    Led,
+    ZenerDiode,
     CurrentSource,
+    FunctionSource,
+    IdealCurrentSource,
     Wire,
+    Ground,
     Resistor,
+    Vcvs,
+    Vccs,
+    Ccvs,
+    Cccs,
+    IdealOpAmp,
+    OpAmp,
+    Npn,
+    Pnp,
+    Nmos,
+    Pmos,
+}
+
+impl ElementType {
+    /// Count of pins placed when the element is added.
+    pub fn pins(self) -> usize {
+        match self {
+            ElementType::Vcvs | ElementType::Vccs => 4,
+            ElementType::IdealOpAmp
+            | ElementType::OpAmp
+            | ElementType::Npn
+            | ElementType::Pnp
+            | ElementType::Nmos
+            | ElementType::Pmos => 3,
+            ElementType::Ground => 1,
+            _ => 2,
+        }
+    }
+
+    /// The element is added as a multi-terminal one even if it has two pins.
+    pub fn stamped(self) -> bool {
+        self.pins() != 2 || matches!(self, ElementType::Ccvs | ElementType::Cccs)
+    }
 }
@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
-use egui::{CentralPanel, Id, Sense};
+use egui::{CentralPanel, Id, LayerId, Sense};
 use egui::{Color32, Margin, Pos2, Shape, Vec2};
 
 use super::action::{Action, MovingObject};
 use super::{AppState, Context, Hovered};
-use crate::element::{ElementPos, Render};
-use crate::element::{CELL_SIZE, SENSABLE_DIST};
+use crate::element::render::render_ground;
+use crate::element::{ground_includes, ElementPos, Properties, Render};
+use crate::element::{CELL_SIZE, HIGHLIGHTED_COLOR, SENSABLE_DIST};
 use crate::utils::Painter;
 
 #[derive(Default)]
@@ -27,6 +28,10 @@ impl Field {
             self.draw_grid(ctx, painter, ui.min_size());
             self.process_elements(state, ctx, painter);
 
+            if *action == Action::None {
+                self.show_readings(state, ctx);
+            }
+
             update_selected(state, ctx, &response);
 
             if response.drag_started_by(egui::PointerButton::Primary) {
@@ -68,20 +73,26 @@ impl Field {
     fn process_elements(&self, state: &mut AppState, ctx: Context, painter: Painter) {
         state.hovered = None;
 
+        // the element controlling the one in the settings is highlighted too
+        let control = state
+            .settings
+            .and_then(|id| state.circuit.try_get_mut(id).ok())
+            .and_then(|element| element.control_mut().and_then(|control| *control));
+
         for (id, element) in state.circuit.iter() {
-            let endpoints = state.circuit.endpoints(id);
+            let pins = state.circuit.terminals(id);
 
-            let mut highlighted = state.selected.contains(&id);
+            let mut highlighted = state.selected.contains(&id) || control == Some(id);
 
             if let Some(mouse_pos) = ctx.mouse_pos() {
                 let grid_mouse_pos = state.transform.inverse() * mouse_pos;
 
-                let hovered = element.includes(endpoints, grid_mouse_pos);
+                let hovered = element.includes(&pins, grid_mouse_pos);
 
                 highlighted = highlighted || hovered;
 
                 if hovered {
-                    if let Some(endpoint) = endpoints.into_iter().position(|point| {
+                    if let Some(endpoint) = pins.iter().position(|point| {
                         point.to_pos().distance_sq(grid_mouse_pos) <= SENSABLE_DIST.powi(2) * 2.0
                     }) {
                         state.hovered = Some(Hovered {
@@ -95,11 +106,41 @@ impl Field {
             }
 
             if highlighted {
-                element.render_highlighted(endpoints, painter);
+                element.render_highlighted(&pins, painter);
             } else {
-                element.render(endpoints, painter);
+                element.render(&pins, painter);
             }
         }
+
+        state.hovered_ground = None;
+
+        for &ground in state.circuit.grounds() {
+            let hovered = ctx.mouse_pos().is_some_and(|mouse_pos| {
+                ground_includes(ground, state.transform.inverse() * mouse_pos)
+            });
+
+            if hovered {
+                state.hovered_ground = Some(ground);
+
+                render_ground(ground, painter, HIGHLIGHTED_COLOR);
+            } else {
+                render_ground(ground, painter, Color32::GRAY);
+            }
+        }
+    }
+
+    fn show_readings(&self, state: &AppState, ctx: Context) {
+        let Some(Hovered { id, .. }) = state.hovered else {
+            return;
+        };
+
+        let reading = state.circuit.operating_point(id);
+
+        egui::show_tooltip_at_pointer(ctx.0, LayerId::background(), Id::new("readings"), |ui| {
+            ui.label(format!("I = {:.3} A", reading.current));
+            ui.label(format!("U = {:.3} V", reading.voltage));
+            ui.label(format!("P = {:.3} W", reading.power));
+        });
     }
 
     fn draw_grid(&self, ctx: Context, painter: Painter, size: Vec2) {
@@ -141,7 +182,7 @@ fn start_moving(ctx: Context<'_>, action: &mut Action, state: &mut AppState<'_>)
             id,
             endpoint: Some(endpoint),
         }) => {
-            let origin_pos = state.circuit.endpoints(id)[endpoint];
+            let origin_pos = state.circuit.terminals(id)[endpoint];
 
             MovingObject::Endpoint {
                 origin_pos,
@@ -156,14 +197,14 @@ fn start_moving(ctx: Context<'_>, action: &mut Action, state: &mut AppState<'_>)
                 origin_endpoints: state
                     .selected
                     .iter()
-                    .map(|&id| (id, state.circuit.endpoints(id)))
+                    .map(|&id| (id, state.circuit.terminals(id)))
                     .collect(),
             }
             .into_moving(mouse_pos)
         }
 
         Some(Hovered { id, endpoint: None }) => {
-            let origin_endpoints = state.circuit.endpoints(id);
+            let origin_endpoints = state.circuit.terminals(id);
 
             MovingObject::Elements {
                 origin_endpoints: HashMap::from_iter([(id, origin_endpoints)]),
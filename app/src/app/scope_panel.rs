@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use egui::{Color32, ComboBox, DragValue, Pos2, Rounding, Sense, Shape, Stroke, Ui, Vec2};
+
+use circuit::circuit::{Probe, Recorder};
+
+use super::control_panel::node_name;
+use super::{AppState, Context};
+use crate::element::ElementPos;
+
+const HORIZONTAL_DIVISIONS: usize = 10;
+const VERTICAL_DIVISIONS: usize = 8;
+const MIN_SCREEN_HEIGHT: f32 = 160.0;
+
+const CHANNEL_COLORS: [Color32; 4] = [
+    Color32::YELLOW,
+    Color32::LIGHT_BLUE,
+    Color32::LIGHT_GREEN,
+    Color32::LIGHT_RED,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Dock {
+    Left,
+    Bottom,
+}
+
+/// Plots the channels of the circuit's recorder, the newest samples are at the right edge
+/// unless the trigger finds a rising edge, then it's at the left one.
+pub struct ScopePanel {
+    dock: Dock,
+    time_per_division: f32,
+    /// Units of a channel per division.
+    scales: HashMap<Probe<ElementPos>, f32>,
+    trigger: Option<Probe<ElementPos>>,
+    level: f32,
+    /// The recorder at the moment of pausing.
+    paused: Option<Recorder<ElementPos>>,
+}
+
+impl Default for ScopePanel {
+    fn default() -> Self {
+        Self {
+            dock: Dock::Left,
+            time_per_division: 0.1,
+            scales: HashMap::new(),
+            trigger: None,
+            level: 0.0,
+            paused: None,
+        }
+    }
+}
+
+impl ScopePanel {
+    pub fn show(&mut self, state: &mut AppState, ctx: Context) {
+        if state.circuit.recorder().probes().is_empty() {
+            self.paused = None;
+
+            return;
+        }
+
+        match self.dock {
+            Dock::Left => {
+                egui::SidePanel::left("scope")
+                    .resizable(true)
+                    .show(ctx.0, |ui| self.contents(ui, state));
+            }
+            Dock::Bottom => {
+                egui::TopBottomPanel::bottom("scope")
+                    .resizable(true)
+                    .show(ctx.0, |ui| self.contents(ui, state));
+            }
+        }
+    }
+
+    fn contents(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.dock, Dock::Left, "left");
+            ui.selectable_value(&mut self.dock, Dock::Bottom, "bottom");
+
+            if ui
+                .selectable_label(self.paused.is_some(), "pause")
+                .clicked()
+            {
+                self.paused = match self.paused {
+                    Some(_) => None,
+                    None => Some(state.circuit.recorder().clone()),
+                };
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("time/div");
+            ui.add(
+                DragValue::new(&mut self.time_per_division)
+                    .speed(0.001)
+                    .range(1e-4..=10.0),
+            );
+        });
+
+        let probes = state.circuit.recorder().probes().to_vec();
+        let mut removed = None;
+
+        for (i, &probe) in probes.iter().enumerate() {
+            let scale = self.scales.entry(probe).or_insert(1.0);
+
+            ui.horizontal(|ui| {
+                ui.colored_label(channel_color(i), channel_name(probe));
+                ui.add(
+                    DragValue::new(scale)
+                        .speed(0.01)
+                        .range(1e-6..=1e6)
+                        .suffix("/div"),
+                );
+
+                if ui.button("remove").clicked() {
+                    removed = Some(probe);
+                }
+            });
+        }
+
+        if let Some(probe) = removed {
+            state.circuit.recorder_mut().remove_probe(probe);
+
+            if let Some(paused) = &mut self.paused {
+                paused.remove_probe(probe);
+            }
+
+            self.scales.remove(&probe);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("trigger");
+
+            ComboBox::from_id_source("scope trigger")
+                .selected_text(self.trigger.map_or("none".to_owned(), channel_name))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.trigger, None, "none");
+
+                    for &probe in &probes {
+                        ui.selectable_value(&mut self.trigger, Some(probe), channel_name(probe));
+                    }
+                });
+
+            ui.add(DragValue::new(&mut self.level).speed(0.1).prefix("level "));
+        });
+
+        let recorder = self.paused.as_ref().unwrap_or(state.circuit.recorder());
+
+        self.screen(ui, recorder);
+    }
+
+    fn screen(&self, ui: &mut Ui, recorder: &Recorder<ElementPos>) {
+        let size = ui.available_size().max(Vec2::new(0.0, MIN_SCREEN_HEIGHT));
+
+        let (response, painter) = ui.allocate_painter(size, Sense::hover());
+        let rect = response.rect;
+
+        painter.rect_filled(rect, Rounding::ZERO, Color32::BLACK);
+
+        let division = Vec2::new(
+            rect.width() / HORIZONTAL_DIVISIONS as f32,
+            rect.height() / VERTICAL_DIVISIONS as f32,
+        );
+        let grid = Stroke::new(1.0, Color32::DARK_GRAY);
+
+        for i in 1..HORIZONTAL_DIVISIONS {
+            let x = rect.left() + i as f32 * division.x;
+
+            painter.line_segment(
+                [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+                grid,
+            );
+        }
+
+        for j in 1..VERTICAL_DIVISIONS {
+            let y = rect.top() + j as f32 * division.y;
+
+            painter.line_segment(
+                [Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)],
+                grid,
+            );
+        }
+
+        let Some(newest) = recorder.samples().last().map(|sample| sample.time) else {
+            return;
+        };
+
+        let window = self.time_per_division * HORIZONTAL_DIVISIONS as f32;
+        let start = self
+            .trigger_time(recorder, newest - window)
+            .unwrap_or(newest - window);
+
+        for (i, &probe) in recorder.probes().iter().enumerate() {
+            let scale = self.scales.get(&probe).copied().unwrap_or(1.0);
+
+            let line = recorder
+                .channel(probe)
+                .filter(|&(time, _)| start <= time && time <= start + window)
+                .map(|(time, value)| {
+                    Pos2::new(
+                        rect.left() + (time - start) / self.time_per_division * division.x,
+                        rect.center().y - value / scale * division.y,
+                    )
+                })
+                .collect();
+
+            painter.add(Shape::line(line, Stroke::new(1.5, channel_color(i))));
+        }
+    }
+
+    /// Time of the last rising edge of the trigger channel through the level
+    /// not later than `latest`, so a whole screen of samples follows it.
+    fn trigger_time(&self, recorder: &Recorder<ElementPos>, latest: f32) -> Option<f32> {
+        let samples: Vec<_> = recorder.channel(self.trigger?).collect();
+
+        samples.windows(2).rev().find_map(|pair| {
+            let [(t0, v0), (t1, v1)] = [pair[0], pair[1]];
+
+            (v0 < self.level && self.level <= v1 && t1 <= latest)
+                .then(|| t0 + (t1 - t0) * (self.level - v0) / (v1 - v0))
+        })
+    }
+}
+
+fn channel_color(i: usize) -> Color32 {
+    CHANNEL_COLORS[i % CHANNEL_COLORS.len()]
+}
+
+pub(super) fn channel_name(probe: Probe<ElementPos>) -> String {
+    match probe {
+        Probe::Voltage(node) => format!("voltage {}", node_name(node)),
+        Probe::Current(id) => format!("current {id:?}"),
+        Probe::Power(id) => format!("power {id:?}"),
+    }
+}
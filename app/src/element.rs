@@ -1,6 +1,23 @@
+mod bjt;
+mod capacitor;
+mod cccs;
+mod ccvs;
+mod controlled_source;
 mod current_source;
+mod diode;
+mod function_source;
+mod ground;
+mod ideal_current_source;
+mod ideal_op_amp;
+mod inductor;
+mod led;
+mod mosfet;
+mod op_amp;
 mod resistor;
+mod vccs;
+mod vcvs;
 mod wire;
+mod zener_diode;
 
 use std::borrow::{Borrow, BorrowMut};
 use std::marker::PhantomData;
@@ -9,16 +26,37 @@ use std::ops;
 use egui::{Color32, Pos2, Rect, Rounding, Vec2};
 use smallvec::SmallVec;
 
+use circuit::circuit::solver::Stamp;
+use circuit::circuit::{AcModel, ElementId, IntegrationMethod};
+use circuit::default_conductors::Waveform;
 use circuit::Conductor;
 
 use crate::utils::Painter;
 
 pub mod render {
+    pub use super::bjt::render_bjt;
+    pub use super::capacitor::render_capacitor;
+    pub use super::cccs::render_cccs;
+    pub use super::ccvs::render_ccvs;
     pub use super::current_source::render_current_source;
+    pub use super::diode::render_diode;
+    pub use super::function_source::render_function_source;
+    pub use super::ground::render_ground;
+    pub use super::ideal_current_source::render_ideal_current_source;
+    pub use super::ideal_op_amp::render_ideal_op_amp;
+    pub use super::inductor::render_inductor;
+    pub use super::led::render_led;
+    pub use super::mosfet::render_mosfet;
+    pub use super::op_amp::render_op_amp;
     pub use super::resistor::render_resistor;
+    pub use super::vccs::render_vccs;
+    pub use super::vcvs::render_vcvs;
     pub use super::wire::render_wire;
+    pub use super::zener_diode::render_zener_diode;
 }
 
+pub use ground::ground_includes;
+
 const CHARGE_VALUE: f32 = 1.0;
 const CHARGE_DISTANCE: f32 = 20.0;
 const CHARGE_SIZE: f32 = 3.0;
@@ -44,8 +82,13 @@ impl<'data> Element<'data> {
         }
     }
 
-    fn render_charges(&self, endpoints: [ElementPos; 2], painter: Painter<'_>) {
-        let endpoints = endpoints.map(ElementPos::to_pos);
+    /// Only the current of two-pin elements is shown.
+    fn render_charges(&self, pins: &[ElementPos], painter: Painter<'_>) {
+        let &[first, second] = pins else {
+            return;
+        };
+
+        let endpoints = [first, second].map(ElementPos::to_pos);
 
         let dist = endpoints[1] - endpoints[0];
         let length = dist.length();
@@ -66,9 +109,19 @@ impl<'data> Element<'data> {
         }
     }
 
-    pub fn includes(&self, endpoints: [ElementPos; 2], point: Pos2) -> bool {
-        let endpoints = endpoints.map(ElementPos::to_pos);
+    /// The point is near the segment between the first two pins or
+    /// a segment from its middle to another pin.
+    pub fn includes(&self, pins: &[ElementPos], point: Pos2) -> bool {
+        let endpoints = [pins[0], pins[1]].map(ElementPos::to_pos);
+        let middle = endpoints[0] + 0.5 * (endpoints[1] - endpoints[0]);
+
+        Self::segment_includes(endpoints, point)
+            || pins[2..]
+                .iter()
+                .any(|pin| Self::segment_includes([middle, pin.to_pos()], point))
+    }
 
+    fn segment_includes(endpoints: [Pos2; 2], point: Pos2) -> bool {
         let min_y = f32::min(endpoints[0].y, endpoints[1].y);
         let max_y = f32::max(endpoints[0].y, endpoints[1].y);
 
@@ -144,6 +197,10 @@ impl<'data> BorrowMut<dyn Conductor + 'data> for Element<'data> {
 }
 
 impl<'data> Conductor for Element<'data> {
+    fn prepare(&mut self, delta_time: f32, method: IntegrationMethod) {
+        self.conductor.prepare(delta_time, method);
+    }
+
     fn zap(&mut self, amperage: f32, delta_time: f32) {
         self.conductor.zap(amperage, delta_time);
 
@@ -153,6 +210,14 @@ impl<'data> Conductor for Element<'data> {
         self.shift = self.shift.rem_euclid(CHARGE_DISTANCE);
     }
 
+    fn save(&mut self) {
+        self.conductor.save();
+    }
+
+    fn restore(&mut self) {
+        self.conductor.restore();
+    }
+
     fn emf(&self) -> f32 {
         self.conductor.emf()
     }
@@ -160,24 +225,56 @@ impl<'data> Conductor for Element<'data> {
     fn resistance(&self) -> f32 {
         self.conductor.resistance()
     }
+
+    fn amperage(&self) -> Option<f32> {
+        self.conductor.amperage()
+    }
+
+    fn linearize(&self, voltage: f32) -> Option<(f32, f32)> {
+        self.conductor.linearize(voltage)
+    }
+
+    fn limit(&self, voltage: f32, previous: f32) -> f32 {
+        self.conductor.limit(voltage, previous)
+    }
+
+    fn limit_potentials(&self, potentials: &mut [f32], previous: &[f32]) {
+        self.conductor.limit_potentials(potentials, previous);
+    }
+
+    fn ac_model(&self, angular_frequency: f32, voltage: f32) -> AcModel {
+        self.conductor.ac_model(angular_frequency, voltage)
+    }
+
+    fn truncation_error(&self) -> Option<f32> {
+        self.conductor.truncation_error()
+    }
+
+    fn control(&self) -> Option<ElementId> {
+        self.conductor.control()
+    }
+
+    fn stamp(&self, stamp: &mut Stamp) {
+        self.conductor.stamp(stamp);
+    }
 }
 
 pub trait Render {
-    fn render(&self, endpoints: [ElementPos; 2], painter: Painter);
-    fn render_highlighted(&self, endpoints: [ElementPos; 2], painter: Painter);
+    fn render(&self, pins: &[ElementPos], painter: Painter);
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter);
 }
 
 impl<'data> Render for Element<'data> {
-    fn render(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        self.conductor.render(endpoints, painter);
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        self.conductor.render(pins, painter);
 
-        self.render_charges(endpoints, painter);
+        self.render_charges(pins, painter);
     }
 
-    fn render_highlighted(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        self.conductor.render_highlighted(endpoints, painter);
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        self.conductor.render_highlighted(pins, painter);
 
-        self.render_charges(endpoints, painter);
+        self.render_charges(pins, painter);
     }
 }
 
@@ -223,6 +320,21 @@ impl ops::Sub<Vec2> for ElementPos {
 pub trait Properties {
     fn properties(&self) -> &'static [&'static str];
     fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]>;
+
+    /// The element whose current controls this one.
+    fn control_mut(&mut self) -> Option<&mut Option<ElementId>> {
+        None
+    }
+
+    /// Pins whose voltage controls the element.
+    fn control_pins(&self) -> Option<[usize; 2]> {
+        None
+    }
+
+    /// The waveform of a time-varying source.
+    fn waveform_mut(&mut self) -> Option<&mut Waveform> {
+        None
+    }
 }
 
 impl<'data> Properties for Element<'data> {
@@ -233,4 +345,16 @@ impl<'data> Properties for Element<'data> {
     fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
         self.conductor.properties_mut()
     }
+
+    fn control_mut(&mut self) -> Option<&mut Option<ElementId>> {
+        self.conductor.control_mut()
+    }
+
+    fn control_pins(&self) -> Option<[usize; 2]> {
+        self.conductor.control_pins()
+    }
+
+    fn waveform_mut(&mut self) -> Option<&mut Waveform> {
+        self.conductor.waveform_mut()
+    }
 }
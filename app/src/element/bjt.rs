@@ -0,0 +1,98 @@
+use egui::{Color32, Pos2, Stroke, Vec2};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::{Bjt, Polarity};
+
+use crate::utils::Painter;
+
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const BAR_DISTANCE: f32 = 8.0;
+const BAR_SIZE: f32 = 16.0;
+const LEG_SIZE: f32 = 8.0;
+const ARROW_SIZE: f32 = 4.0;
+
+impl Render for Bjt {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_bjt(pins, painter, Color32::GRAY, self.polarity);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_bjt(pins, painter, HIGHLIGHTED_COLOR, self.polarity);
+    }
+}
+
+/// The pins are `[collector, base, emitter]`, the emitter may be missing
+/// while the transistor is being placed.
+pub fn render_bjt(pins: &[ElementPos], painter: Painter<'_>, color: Color32, polarity: Polarity) {
+    let stroke = Stroke::new(2.0, color);
+
+    let (pins, bar, along, across) = layout(pins);
+    let [collector, base, emitter] = pins;
+
+    painter.line([base, bar], stroke);
+    painter.line(
+        [bar - 0.5 * BAR_SIZE * across, bar + 0.5 * BAR_SIZE * across],
+        stroke,
+    );
+
+    let collector_leg = bar + 0.25 * BAR_SIZE * across;
+    let collector_end = bar + LEG_SIZE * along + 0.5 * BAR_SIZE * across;
+
+    painter.line([collector_leg, collector_end], stroke);
+    painter.line([collector_end, collector], stroke);
+
+    let emitter_leg = bar - 0.25 * BAR_SIZE * across;
+    let emitter_end = bar + LEG_SIZE * along - 0.5 * BAR_SIZE * across;
+
+    painter.line([emitter_leg, emitter_end], stroke);
+    painter.line([emitter_end, emitter], stroke);
+
+    // the arrow shows the direction of the emitter current
+    let (from, to) = match polarity {
+        Polarity::N => (emitter_leg, emitter_end),
+        Polarity::P => (emitter_end, emitter_leg),
+    };
+
+    render_arrow([from, to], painter, stroke);
+}
+
+/// Places the bar of a transistor symbol in front of the middle pin facing the others,
+/// returns the positions of the pins, the center of the bar and the directions
+/// away from the middle pin and towards the first pin.
+pub(super) fn layout(pins: &[ElementPos]) -> ([Pos2; 3], Pos2, Vec2, Vec2) {
+    let [first, middle] = [pins[0], pins[1]].map(ElementPos::to_pos);
+    let last = pins
+        .get(2)
+        .map_or(first + (middle - first).rot90(), |pin| pin.to_pos());
+
+    let center = first + 0.5 * (last - first);
+
+    let along = (center - middle).normalized();
+    let across = match along.rot90() {
+        across if across.dot(first - center) < 0.0 => -across,
+        across => across,
+    };
+
+    let bar = center - BAR_DISTANCE.min((center - middle).length()) * along;
+
+    ([first, middle, last], bar, along, across)
+}
+
+/// Draws the head of an arrow at the end of the segment.
+pub(super) fn render_arrow([from, to]: [Pos2; 2], painter: Painter<'_>, stroke: Stroke) {
+    let d = ARROW_SIZE * (to - from).normalized();
+
+    painter.line([to, to - d + d.rot90()], stroke);
+    painter.line([to, to - d - d.rot90()], stroke);
+}
+
+impl Properties for Bjt {
+    fn properties(&self) -> &'static [&'static str] {
+        &["beta", "saturation current"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.beta, &mut self.saturation_current]
+    }
+}
@@ -0,0 +1,50 @@
+use egui::{Color32, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::Capacitor;
+
+use crate::utils::Painter;
+
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const CAPACITOR_DISTANCE: f32 = 6.0;
+const CAPACITOR_SIZE: f32 = 16.0;
+
+impl Render for Capacitor {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_capacitor([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_capacitor([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_capacitor(endpoints: [ElementPos; 2], painter: Painter<'_>, color: Color32) {
+    let endpoints = endpoints.map(ElementPos::to_pos);
+
+    let stroke = Stroke::new(2.0, color);
+
+    let l = endpoints[1] - endpoints[0];
+    let length = l.length();
+
+    let d = 0.5 * (length - CAPACITOR_DISTANCE) * l.normalized();
+
+    painter.line([endpoints[0], endpoints[0] + d], stroke);
+    painter.line([endpoints[1], endpoints[1] - d], stroke);
+
+    let f = 0.5 * CAPACITOR_SIZE * l.normalized().rot90();
+
+    painter.line([endpoints[0] + d - f, endpoints[0] + d + f], stroke);
+    painter.line([endpoints[1] - d - f, endpoints[1] - d + f], stroke);
+}
+
+impl Properties for Capacitor {
+    fn properties(&self) -> &'static [&'static str] {
+        &["capacitance"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.capacitance]
+    }
+}
@@ -0,0 +1,38 @@
+use egui::Color32;
+use smallvec::{smallvec, SmallVec};
+
+use circuit::circuit::ElementId;
+use circuit::default_conductors::Cccs;
+
+use crate::utils::Painter;
+
+use super::controlled_source::render_diamond;
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+impl Render for Cccs {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_cccs([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_cccs([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_cccs(endpoints: [ElementPos; 2], painter: Painter<'_>, color: Color32) {
+    render_diamond(endpoints, painter, color, false);
+}
+
+impl Properties for Cccs {
+    fn properties(&self) -> &'static [&'static str] {
+        &["gain"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.gain]
+    }
+
+    fn control_mut(&mut self) -> Option<&mut Option<ElementId>> {
+        Some(&mut self.control)
+    }
+}
@@ -0,0 +1,95 @@
+use egui::epaint::PathShape;
+use egui::{Color32, Pos2, Shape, Stroke};
+
+use crate::utils::Painter;
+
+use super::ElementPos;
+
+const CONTROLLED_SOURCE_SIZE: f32 = 16.0;
+const SIGN_SIZE: f32 = 2.5;
+const ARROW_SIZE: f32 = 2.5;
+const CONTROL_DASH: f32 = 4.0;
+const CONTROL_PIN_RADIUS: f32 = 3.0;
+
+/// Draws the leads and the diamond between `endpoints` with the signs of a voltage source
+/// or the arrow of a current source, returns the center of the diamond.
+pub(super) fn render_diamond(
+    endpoints: [ElementPos; 2],
+    painter: Painter<'_>,
+    color: Color32,
+    voltage: bool,
+) -> Pos2 {
+    let endpoints = endpoints.map(ElementPos::to_pos);
+
+    let stroke = Stroke::new(2.0, color);
+
+    let l = endpoints[1] - endpoints[0];
+    let length = l.length();
+
+    let d = 0.5 * (length - CONTROLLED_SOURCE_SIZE) * l.normalized();
+
+    painter.line([endpoints[0], endpoints[0] + d], stroke);
+    painter.line([endpoints[1], endpoints[1] - d], stroke);
+
+    let center = endpoints[0] + 0.5 * l;
+
+    let h = 0.5 * CONTROLLED_SOURCE_SIZE * l.normalized();
+    let f = 0.5 * CONTROLLED_SOURCE_SIZE * l.normalized().rot90();
+
+    painter.render(PathShape {
+        points: vec![center - h, center + f, center + h, center - f],
+        closed: true,
+        fill: Color32::TRANSPARENT,
+        stroke: stroke.into(),
+    });
+
+    let stroke = Stroke::new(1.5, color);
+
+    let a = 0.45 * h;
+    let s = SIGN_SIZE * l.normalized();
+    let t = SIGN_SIZE * l.normalized().rot90();
+
+    if voltage {
+        // `+` is at the first endpoint
+        painter.line([center - a - s, center - a + s], stroke);
+        painter.line([center - a - t, center - a + t], stroke);
+        painter.line([center + a - t, center + a + t], stroke);
+    } else {
+        // the arrow points in the direction of the current
+        let f = ARROW_SIZE * l.normalized().rot90();
+        let h = ARROW_SIZE * l.normalized();
+
+        painter.line([center - a, center + a], stroke);
+        painter.line([center + a, center + a - h + f], stroke);
+        painter.line([center + a, center + a - h - f], stroke);
+    }
+
+    center
+}
+
+/// Dashed lines from the control pins to the source, the positive pin is filled.
+pub(super) fn render_control(
+    pins: &[ElementPos],
+    center: Pos2,
+    painter: Painter<'_>,
+    color: Color32,
+) {
+    let stroke = Stroke::new(1.0, color);
+
+    for (idx, pin) in pins.iter().enumerate() {
+        let pin = pin.to_pos();
+
+        painter.render(Shape::dashed_line(
+            &[pin, center],
+            stroke,
+            CONTROL_DASH,
+            CONTROL_DASH,
+        ));
+
+        if idx == 0 {
+            painter.render(Shape::circle_filled(pin, CONTROL_PIN_RADIUS, color));
+        } else {
+            painter.render(Shape::circle_stroke(pin, CONTROL_PIN_RADIUS, stroke));
+        }
+    }
+}
@@ -11,12 +11,12 @@ const CURRENT_SOURCE_DISTANCE: f32 = 5.0;
 const CURRENT_SOURCE_SIZE: f32 = 10.0;
 
 impl Render for CurrentSource {
-    fn render(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        render_current_source(endpoints, painter, Color32::GRAY);
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_current_source([pins[0], pins[1]], painter, Color32::GRAY);
     }
 
-    fn render_highlighted(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        render_current_source(endpoints, painter, HIGHLIGHTED_COLOR);
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_current_source([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
     }
 }
 
@@ -0,0 +1,70 @@
+use egui::epaint::PathShape;
+use egui::{Color32, Pos2, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::Diode;
+
+use crate::utils::Painter;
+
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const DIODE_WIDTH: f32 = 12.0;
+const DIODE_SIZE: f32 = 12.0;
+
+impl Render for Diode {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_diode([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_diode([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_diode(endpoints: [ElementPos; 2], painter: Painter<'_>, color: Color32) {
+    let bar = render_junction(endpoints, painter, color, color);
+
+    painter.line(bar, Stroke::new(2.0, color));
+}
+
+/// Draws the leads and the triangle pointing from `endpoints[0]` to `endpoints[1]`,
+/// returns the ends of the bar at its tip.
+pub(super) fn render_junction(
+    endpoints: [ElementPos; 2],
+    painter: Painter<'_>,
+    color: Color32,
+    fill: Color32,
+) -> [Pos2; 2] {
+    let endpoints = endpoints.map(ElementPos::to_pos);
+
+    let stroke = Stroke::new(2.0, color);
+
+    let l = endpoints[1] - endpoints[0];
+    let length = l.length();
+
+    let d = 0.5 * (length - DIODE_WIDTH) * l.normalized();
+
+    painter.line([endpoints[0], endpoints[0] + d], stroke);
+    painter.line([endpoints[1], endpoints[1] - d], stroke);
+
+    let f = 0.5 * DIODE_SIZE * l.normalized().rot90();
+
+    painter.render(PathShape {
+        points: vec![endpoints[0] + d + f, endpoints[0] + d - f, endpoints[1] - d],
+        closed: true,
+        fill,
+        stroke: stroke.into(),
+    });
+
+    [endpoints[1] - d + f, endpoints[1] - d - f]
+}
+
+impl Properties for Diode {
+    fn properties(&self) -> &'static [&'static str] {
+        &["saturation current", "emission coefficient"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.saturation_current, &mut self.emission_coefficient]
+    }
+}
@@ -0,0 +1,118 @@
+use egui::{Color32, Shape, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::{FunctionSource, Waveform};
+
+use crate::utils::Painter;
+
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const FUNCTION_SOURCE_RADIUS: f32 = 8.0;
+const WAVE_POINTS: usize = 16;
+
+impl Render for FunctionSource {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_function_source([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_function_source([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_function_source(endpoints: [ElementPos; 2], painter: Painter<'_>, color: Color32) {
+    let endpoints = endpoints.map(ElementPos::to_pos);
+
+    let stroke = Stroke::new(2.0, color);
+
+    let l = endpoints[1] - endpoints[0];
+    let length = l.length();
+
+    let d = 0.5 * (length - 2.0 * FUNCTION_SOURCE_RADIUS) * l.normalized();
+
+    painter.line([endpoints[0], endpoints[0] + d], stroke);
+    painter.line([endpoints[1], endpoints[1] - d], stroke);
+
+    let center = endpoints[0] + 0.5 * l;
+
+    painter.render(Shape::circle_stroke(center, FUNCTION_SOURCE_RADIUS, stroke));
+
+    // a period of a sine across the circle
+    let a = 0.6 * FUNCTION_SOURCE_RADIUS * l.normalized().rot90();
+    let h = 0.4 * FUNCTION_SOURCE_RADIUS * l.normalized();
+
+    let wave = (0..=WAVE_POINTS)
+        .map(|i| {
+            let x = i as f32 / WAVE_POINTS as f32;
+
+            center + (2.0 * x - 1.0) * a + (std::f32::consts::TAU * x).sin() * h
+        })
+        .collect();
+
+    painter.render(Shape::line(wave, Stroke::new(1.0, color)));
+}
+
+impl Properties for FunctionSource {
+    fn properties(&self) -> &'static [&'static str] {
+        match self.waveform {
+            Waveform::Sine { .. } => &["resistance", "amplitude", "frequency", "phase", "offset"],
+            Waveform::Square { .. } => &[
+                "resistance",
+                "amplitude",
+                "frequency",
+                "duty cycle",
+                "offset",
+            ],
+            Waveform::Triangle { .. } => &["resistance", "amplitude", "frequency", "offset"],
+            Waveform::Pulse { .. } => &[
+                "resistance",
+                "low",
+                "high",
+                "delay",
+                "rise",
+                "width",
+                "fall",
+                "period",
+            ],
+            Waveform::PiecewiseLinear(_) => &["resistance"],
+        }
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        let resistance = &mut self.resistance;
+
+        match &mut self.waveform {
+            Waveform::Sine {
+                amplitude,
+                frequency,
+                phase,
+                offset,
+            } => smallvec![resistance, amplitude, frequency, phase, offset],
+            Waveform::Square {
+                amplitude,
+                frequency,
+                duty_cycle,
+                offset,
+            } => smallvec![resistance, amplitude, frequency, duty_cycle, offset],
+            Waveform::Triangle {
+                amplitude,
+                frequency,
+                offset,
+            } => smallvec![resistance, amplitude, frequency, offset],
+            Waveform::Pulse {
+                low,
+                high,
+                delay,
+                rise,
+                width,
+                fall,
+                period,
+            } => smallvec![resistance, low, high, delay, rise, width, fall, period],
+            Waveform::PiecewiseLinear(_) => smallvec![resistance],
+        }
+    }
+
+    fn waveform_mut(&mut self) -> Option<&mut Waveform> {
+        Some(&mut self.waveform)
+    }
+}
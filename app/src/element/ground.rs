@@ -0,0 +1,37 @@
+use egui::{Color32, Pos2, Stroke, Vec2};
+
+use crate::utils::Painter;
+
+use super::{ElementPos, SENSABLE_DIST};
+
+const GROUND_LEAD: f32 = 10.0;
+const GROUND_WIDTH: f32 = 16.0;
+const GROUND_LINES: usize = 3;
+const GROUND_SPACING: f32 = 4.0;
+
+/// The symbol hangs below the grounded node.
+pub fn render_ground(node: ElementPos, painter: Painter<'_>, color: Color32) {
+    let node = node.to_pos();
+
+    let stroke = Stroke::new(2.0, color);
+
+    let base = node + Vec2::new(0.0, GROUND_LEAD);
+
+    painter.line([node, base], stroke);
+
+    for i in 0..GROUND_LINES {
+        let center = base + Vec2::new(0.0, i as f32 * GROUND_SPACING);
+        let half = Vec2::new(
+            0.5 * GROUND_WIDTH * (GROUND_LINES - i) as f32 / GROUND_LINES as f32,
+            0.0,
+        );
+
+        painter.line([center - half, center + half], stroke);
+    }
+}
+
+pub fn ground_includes(node: ElementPos, point: Pos2) -> bool {
+    let center = node.to_pos() + Vec2::new(0.0, GROUND_LEAD);
+
+    center.distance(point) <= SENSABLE_DIST
+}
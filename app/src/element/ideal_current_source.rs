@@ -0,0 +1,66 @@
+use egui::{Color32, Shape, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::IdealCurrentSource;
+
+use crate::utils::Painter;
+
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const IDEAL_CURRENT_SOURCE_RADIUS: f32 = 8.0;
+const ARROW_SIZE: f32 = 3.0;
+
+impl Render for IdealCurrentSource {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_ideal_current_source([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_ideal_current_source([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_ideal_current_source(
+    endpoints: [ElementPos; 2],
+    painter: Painter<'_>,
+    color: Color32,
+) {
+    let endpoints = endpoints.map(ElementPos::to_pos);
+
+    let stroke = Stroke::new(2.0, color);
+
+    let l = endpoints[1] - endpoints[0];
+    let length = l.length();
+
+    let d = 0.5 * (length - 2.0 * IDEAL_CURRENT_SOURCE_RADIUS) * l.normalized();
+
+    painter.line([endpoints[0], endpoints[0] + d], stroke);
+    painter.line([endpoints[1], endpoints[1] - d], stroke);
+
+    let center = endpoints[0] + 0.5 * l;
+
+    painter.render(Shape::circle_stroke(
+        center,
+        IDEAL_CURRENT_SOURCE_RADIUS,
+        stroke,
+    ));
+
+    // the arrow points in the direction of the forced current
+    let a = 0.6 * IDEAL_CURRENT_SOURCE_RADIUS * l.normalized();
+    let f = ARROW_SIZE * l.normalized().rot90();
+    let h = ARROW_SIZE * l.normalized();
+
+    painter.line([center - a, center + a], stroke);
+    painter.line([center + a, center + a - h + f], stroke);
+    painter.line([center + a, center + a - h - f], stroke);
+}
+
+impl Properties for IdealCurrentSource {
+    fn properties(&self) -> &'static [&'static str] {
+        &["amperage"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.amperage]
+    }
+}
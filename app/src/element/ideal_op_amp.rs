@@ -0,0 +1,34 @@
+use egui::Color32;
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::IdealOpAmp;
+
+use crate::utils::Painter;
+
+use super::op_amp::render_triangle;
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+impl Render for IdealOpAmp {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_ideal_op_amp(pins, painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_ideal_op_amp(pins, painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+/// The output pin may be missing while the op-amp is being placed.
+pub fn render_ideal_op_amp(pins: &[ElementPos], painter: Painter<'_>, color: Color32) {
+    render_triangle(pins, painter, color, false);
+}
+
+impl Properties for IdealOpAmp {
+    fn properties(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![]
+    }
+}
@@ -0,0 +1,67 @@
+use std::f32::consts::PI;
+
+use egui::epaint::PathShape;
+use egui::{Color32, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::Inductor;
+
+use crate::utils::Painter;
+
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const INDUCTOR_WIDTH: f32 = 24.0;
+const INDUCTOR_TURNS: usize = 4;
+const TURN_SEGMENTS: usize = 8;
+
+impl Render for Inductor {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_inductor([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_inductor([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_inductor(endpoints: [ElementPos; 2], painter: Painter<'_>, color: Color32) {
+    let endpoints = endpoints.map(ElementPos::to_pos);
+
+    let stroke = Stroke::new(2.0, color);
+
+    let l = endpoints[1] - endpoints[0];
+    let length = l.length();
+
+    let d = 0.5 * (length - INDUCTOR_WIDTH) * l.normalized();
+
+    painter.line([endpoints[0], endpoints[0] + d], stroke);
+    painter.line([endpoints[1], endpoints[1] - d], stroke);
+
+    let radius = 0.5 * INDUCTOR_WIDTH / INDUCTOR_TURNS as f32;
+
+    let along = radius * l.normalized();
+    let across = radius * l.normalized().rot90();
+
+    // every turn is a half circle
+    let points = (0..INDUCTOR_TURNS).flat_map(|turn| {
+        let center = endpoints[0] + d + (2 * turn + 1) as f32 * along;
+
+        (0..=TURN_SEGMENTS).map(move |segment| {
+            let angle = PI * (1.0 - segment as f32 / TURN_SEGMENTS as f32);
+
+            center + angle.cos() * along - angle.sin() * across
+        })
+    });
+
+    painter.render(PathShape::line(points.collect(), stroke));
+}
+
+impl Properties for Inductor {
+    fn properties(&self) -> &'static [&'static str] {
+        &["inductance"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.inductance]
+    }
+}
@@ -0,0 +1,57 @@
+use egui::{Color32, Shape, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::{Led, LED_NOMINAL_CURRENT};
+
+use crate::utils::Painter;
+
+use super::diode::render_junction;
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const GLOW_RADIUS: f32 = 15.0;
+
+impl Render for Led {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_glow(self, [pins[0], pins[1]], painter);
+        render_led([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_glow(self, [pins[0], pins[1]], painter);
+        render_led([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_led(endpoints: [ElementPos; 2], painter: Painter<'_>, color: Color32) {
+    let bar = render_junction(endpoints, painter, color, Color32::TRANSPARENT);
+
+    painter.line(bar, Stroke::new(2.0, color));
+}
+
+/// The glow is as bright as the current is close to the nominal one.
+fn render_glow(led: &Led, endpoints: [ElementPos; 2], painter: Painter<'_>) {
+    let brightness = (led.current() / LED_NOMINAL_CURRENT as f32).clamp(0.0, 1.0);
+
+    if brightness == 0.0 {
+        return;
+    }
+
+    let [r, g, b] = led.color;
+    let center = endpoints[0].to_pos() + 0.5 * (endpoints[1].to_pos() - endpoints[0].to_pos());
+
+    painter.render(Shape::circle_filled(
+        center,
+        GLOW_RADIUS,
+        Color32::from_rgba_unmultiplied(r, g, b, (brightness * 192.0) as u8),
+    ));
+}
+
+impl Properties for Led {
+    fn properties(&self) -> &'static [&'static str] {
+        &["forward voltage"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.forward_voltage]
+    }
+}
@@ -0,0 +1,71 @@
+use egui::{Color32, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::{Mosfet, Polarity};
+
+use crate::utils::Painter;
+
+use super::bjt::{layout, render_arrow};
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const GATE_GAP: f32 = 4.0;
+const CHANNEL_SIZE: f32 = 16.0;
+
+impl Render for Mosfet {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_mosfet(pins, painter, Color32::GRAY, self.polarity);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_mosfet(pins, painter, HIGHLIGHTED_COLOR, self.polarity);
+    }
+}
+
+/// The pins are `[drain, gate, source]`, the source may be missing
+/// while the transistor is being placed.
+pub fn render_mosfet(
+    pins: &[ElementPos],
+    painter: Painter<'_>,
+    color: Color32,
+    polarity: Polarity,
+) {
+    let stroke = Stroke::new(2.0, color);
+
+    let (pins, gate, along, across) = layout(pins);
+    let [drain, gate_pin, source] = pins;
+
+    let half = 0.5 * CHANNEL_SIZE * across;
+
+    painter.line([gate_pin, gate], stroke);
+    painter.line([gate - half, gate + half], stroke);
+
+    let channel = gate + GATE_GAP * along;
+
+    painter.line([channel - half, channel + half], stroke);
+
+    let drain_end = channel + 0.4 * CHANNEL_SIZE * across;
+    let source_end = channel - 0.4 * CHANNEL_SIZE * across;
+
+    painter.line([drain_end, drain], stroke);
+    painter.line([source_end, source], stroke);
+
+    // the arrow points along the current of the channel at the source
+    let (from, to) = match polarity {
+        Polarity::N => (source_end, source),
+        Polarity::P => (source, source_end),
+    };
+
+    let middle = from + 0.5 * (to - from);
+
+    render_arrow([from, middle], painter, stroke);
+}
+
+impl Properties for Mosfet {
+    fn properties(&self) -> &'static [&'static str] {
+        &["threshold voltage", "transconductance"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.threshold_voltage, &mut self.transconductance]
+    }
+}
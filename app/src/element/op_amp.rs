@@ -0,0 +1,97 @@
+use egui::epaint::PathShape;
+use egui::{Color32, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::OpAmp;
+
+use crate::utils::Painter;
+
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const SIGN_SIZE: f32 = 2.5;
+const RAIL_LENGTH: f32 = 6.0;
+
+impl Render for OpAmp {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_op_amp(pins, painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_op_amp(pins, painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+/// The output pin may be missing while the op-amp is being placed.
+pub fn render_op_amp(pins: &[ElementPos], painter: Painter<'_>, color: Color32) {
+    render_triangle(pins, painter, color, true);
+}
+
+/// Draws the triangle with its base between the inputs pointing to the output
+/// and the lead to it, `rails` adds the stubs of the supply rails.
+pub(super) fn render_triangle(
+    pins: &[ElementPos],
+    painter: Painter<'_>,
+    color: Color32,
+    rails: bool,
+) {
+    let [a, b] = [pins[0], pins[1]].map(ElementPos::to_pos);
+    let output = pins.get(2).map(|pin| pin.to_pos());
+
+    let stroke = Stroke::new(2.0, color);
+
+    let base = b - a;
+    let middle = a + 0.5 * base;
+
+    let dir = match output {
+        Some(output) => (output - middle).normalized(),
+        None => base.normalized().rot90(),
+    };
+
+    let apex = middle + base.length() * dir;
+
+    painter.render(PathShape {
+        points: vec![a, b, apex],
+        closed: true,
+        fill: Color32::TRANSPARENT,
+        stroke: stroke.into(),
+    });
+
+    if let Some(output) = output {
+        painter.line([apex, output], stroke);
+    }
+
+    if rails {
+        let r = RAIL_LENGTH * base.normalized();
+
+        painter.line([a + 0.5 * (apex - a), a + 0.5 * (apex - a) - r], stroke);
+        painter.line([b + 0.5 * (apex - b), b + 0.5 * (apex - b) + r], stroke);
+    }
+
+    let stroke = Stroke::new(1.5, color);
+
+    let s = SIGN_SIZE * base.normalized();
+    let t = SIGN_SIZE * dir;
+    let h = 0.25 * base.length() * dir;
+
+    // `+` is at the non-inverting input
+    let plus = a + 0.25 * base + h;
+    let minus = b - 0.25 * base + h;
+
+    painter.line([plus - s, plus + s], stroke);
+    painter.line([plus - t, plus + t], stroke);
+    painter.line([minus - s, minus + s], stroke);
+}
+
+impl Properties for OpAmp {
+    fn properties(&self) -> &'static [&'static str] {
+        &["gain", "positive rail", "negative rail"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![
+            &mut self.gain,
+            &mut self.positive_rail,
+            &mut self.negative_rail
+        ]
+    }
+}
@@ -12,12 +12,12 @@ const RESISTOR_WIDTH: f32 = 20.0;
 const RESISTOR_HEIGHT: f32 = 5.0;
 
 impl Render for Resistor {
-    fn render(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        render_resistor(endpoints, painter, Color32::GRAY);
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_resistor([pins[0], pins[1]], painter, Color32::GRAY);
     }
 
-    fn render_highlighted(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        render_resistor(endpoints, painter, HIGHLIGHTED_COLOR);
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_resistor([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
     }
 }
 
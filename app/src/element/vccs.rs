@@ -0,0 +1,40 @@
+use egui::Color32;
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::Vccs;
+
+use crate::utils::Painter;
+
+use super::controlled_source::{render_control, render_diamond};
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+impl Render for Vccs {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_vccs(pins, painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_vccs(pins, painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+/// The control pins may be missing while the source is being placed.
+pub fn render_vccs(pins: &[ElementPos], painter: Painter<'_>, color: Color32) {
+    let center = render_diamond([pins[0], pins[1]], painter, color, false);
+
+    render_control(&pins[2..], center, painter, color);
+}
+
+impl Properties for Vccs {
+    fn properties(&self) -> &'static [&'static str] {
+        &["transconductance"]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![&mut self.transconductance]
+    }
+
+    fn control_pins(&self) -> Option<[usize; 2]> {
+        Some([2, 3])
+    }
+}
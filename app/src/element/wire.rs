@@ -7,12 +7,12 @@ use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
 use crate::utils::Painter;
 
 impl Render for Wire {
-    fn render(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        render_wire(endpoints, painter, Color32::GRAY);
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_wire([pins[0], pins[1]], painter, Color32::GRAY);
     }
 
-    fn render_highlighted(&self, endpoints: [ElementPos; 2], painter: Painter) {
-        render_wire(endpoints, painter, HIGHLIGHTED_COLOR);
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_wire([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
     }
 }
 
@@ -0,0 +1,51 @@
+use egui::{Color32, Stroke};
+use smallvec::{smallvec, SmallVec};
+
+use circuit::default_conductors::ZenerDiode;
+
+use crate::utils::Painter;
+
+use super::diode::render_junction;
+use super::{ElementPos, Properties, Render, HIGHLIGHTED_COLOR};
+
+const ZENER_WING_SIZE: f32 = 3.0;
+
+impl Render for ZenerDiode {
+    fn render(&self, pins: &[ElementPos], painter: Painter) {
+        render_zener_diode([pins[0], pins[1]], painter, Color32::GRAY);
+    }
+
+    fn render_highlighted(&self, pins: &[ElementPos], painter: Painter) {
+        render_zener_diode([pins[0], pins[1]], painter, HIGHLIGHTED_COLOR);
+    }
+}
+
+pub fn render_zener_diode(endpoints: [ElementPos; 2], painter: Painter<'_>, color: Color32) {
+    let bar = render_junction(endpoints, painter, color, color);
+
+    let stroke = Stroke::new(2.0, color);
+
+    let along = ZENER_WING_SIZE * (endpoints[1].to_pos() - endpoints[0].to_pos()).normalized();
+
+    painter.line(bar, stroke);
+    painter.line([bar[0], bar[0] - along], stroke);
+    painter.line([bar[1], bar[1] + along], stroke);
+}
+
+impl Properties for ZenerDiode {
+    fn properties(&self) -> &'static [&'static str] {
+        &[
+            "saturation current",
+            "emission coefficient",
+            "breakdown voltage",
+        ]
+    }
+
+    fn properties_mut(&mut self) -> SmallVec<[&mut f32; 2]> {
+        smallvec![
+            &mut self.saturation_current,
+            &mut self.emission_coefficient,
+            &mut self.breakdown_voltage,
+        ]
+    }
+}
@@ -0,0 +1,90 @@
+//! Builds ladder or grid networks of resistors and measures how fast they are updated.
+//!
+//! `cargo bench -p circuit --features default_conductors -- [ladder|grid] [size] [mna|loops] [updates]`
+
+use std::env;
+use std::time::Instant;
+
+use circuit::circuit::solver::LoopSolver;
+use circuit::default_conductors::{CurrentSource, Resistor};
+use circuit::{Circuit, Conductor};
+
+type Network = Circuit<'static, Box<dyn Conductor>, (usize, usize)>;
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).filter(|arg| !arg.starts_with('-')).collect();
+
+    let kind = args.first().map_or("ladder", String::as_str);
+    let size = args.get(1).map_or(1000, |size| size.parse().unwrap());
+    let solver = args.get(2).map_or("mna", String::as_str);
+    let updates = args.get(3).map_or(10, |updates| updates.parse().unwrap());
+
+    let start = Instant::now();
+
+    let mut circuit = match kind {
+        "ladder" => ladder(size),
+        "grid" => grid(size),
+        _ => panic!("unknown network `{kind}`, expected `ladder` or `grid`"),
+    };
+
+    match solver {
+        "mna" => {}
+        "loops" => circuit.set_solver(LoopSolver),
+        _ => panic!("unknown solver `{solver}`, expected `mna` or `loops`"),
+    }
+
+    let build_time = start.elapsed();
+
+    // a single step per update
+    let delta_time = circuit.transient_options().max_step;
+
+    let start = Instant::now();
+
+    for _ in 0..updates {
+        circuit.update(delta_time);
+    }
+
+    let update_time = start.elapsed() / updates;
+
+    println!(
+        "{kind} {size} ({} elements): built in {build_time:?}, {solver} update in {update_time:?}",
+        circuit.iter().count(),
+    );
+}
+
+/// A source followed by `size` sections of a series and a shunt resistors.
+fn ladder(size: usize) -> Network {
+    let mut circuit = Network::default();
+
+    circuit.add([(0, 0), (0, 1)], Box::new(CurrentSource::new(10.0, 0.0)));
+
+    for i in 1..=size {
+        circuit.add([(0, i), (0, i + 1)], Box::new(Resistor::new(1.0)));
+        circuit.add([(0, i + 1), (0, 0)], Box::new(Resistor::new(2.0)));
+    }
+
+    circuit
+}
+
+/// A `size` by `size` mesh of resistors with a source between the opposite corners.
+fn grid(size: usize) -> Network {
+    let mut circuit = Network::default();
+
+    for x in 0..size {
+        for y in 0..size {
+            if x + 1 < size {
+                circuit.add([(x, y), (x + 1, y)], Box::new(Resistor::new(1.0)));
+            }
+
+            if y + 1 < size {
+                circuit.add([(x, y), (x, y + 1)], Box::new(Resistor::new(1.0)));
+            }
+        }
+    }
+
+    let corners = [(0, 0), (size - 1, size - 1)];
+
+    circuit.add(corners, Box::new(CurrentSource::new(10.0, 1.0)));
+
+    circuit
+}
@@ -1,136 +1,977 @@
-mod bfs;
+mod ac;
+mod adjacency;
+mod dc;
+mod disjoint_set;
+mod error;
 mod graph;
-mod matrix;
+mod newton;
+mod probe;
+mod recorder;
+pub mod solver;
 mod spanning_forest;
+#[cfg(all(test, feature = "default_conductors"))]
+mod tests;
+mod transient;
 
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::marker::PhantomData;
+use std::ops::RangeInclusive;
 
 use bimap::BiHashMap;
-use nalgebra::DMatrix;
+use nalgebra::{Complex, RealField};
 
 use crate::conductor::Conductor;
+use ac::{AcBranch, AcNetwork};
+use adjacency::{EdgeIndex, NodeIndex};
 use graph::CircuitGraph;
-use matrix::NodeIndex;
+use solver::{Branch, MnaSolver, Network, Solution, Solver, Stamp};
 
-pub struct Circuit<'data, C, N>
+pub use ac::{AcModel, AcSolution, BodePoint, SweepScale};
+pub use dc::{DcPoint, SweepParameter};
+pub use error::SolveError;
+pub use newton::NewtonOptions;
+pub use probe::Probe;
+pub use recorder::{Recorder, Sample};
+pub use transient::{IntegrationMethod, Integrator, TransientOptions};
+
+/// Limit of substeps a step of [`TransientOptions::max_step`] can be split into.
+const MAX_SUBSTEPS: usize = 1024;
+
+pub struct Circuit<'data, C, N, T = f32>
 where
-    C: BorrowMut<dyn Conductor + 'data>,
+    C: BorrowMut<dyn Conductor<T> + 'data>,
     N: Copy + Hash + Eq,
+    T: RealField + Copy,
 {
-    graph: CircuitGraph,
-    elements: HashMap<ElementId, CircuitElement<'data, C>>,
+    graph: CircuitGraph<T>,
+    elements: HashMap<ElementId, CircuitElement<'data, C, T>>,
     nodes: BiHashMap<NodeIndex, N>,
 
     ids: Vec<ElementId>,
     ids_count: usize,
 
-    resistances: DMatrix<f32>,
-    emf: DMatrix<f32>,
+    solver: Box<dyn Solver<T>>,
+
+    transient: TransientOptions<T>,
+    newton: NewtonOptions<T>,
+    /// The time of the last step which changed the elements' state is
+    /// `start + ticks * max_step / MAX_SUBSTEPS`, counting the ticks keeps it from
+    /// stalling when the step is small compared to it.
+    start: T,
+    ticks: u64,
+    /// Time passed to the circuit since that step.
+    pending: T,
+    substeps: usize,
+
+    grounds: Vec<N>,
+    potentials: Vec<T>,
+    parts: Vec<usize>,
+
+    recorder: Recorder<N, T>,
 
     lt: PhantomData<&'data ()>,
 }
 
-impl<'data, C, N> Circuit<'data, C, N>
+impl<'data, C, N, T> Circuit<'data, C, N, T>
 where
-    C: BorrowMut<dyn Conductor + 'data>,
+    C: BorrowMut<dyn Conductor<T> + 'data>,
     N: Copy + Hash + Eq,
+    T: RealField + Copy,
 {
-    pub fn update(&mut self, delta_time: f32) {
-        let (loops, loops_transposed) = self.graph.loops();
+    /// Panics if the circuit can't be solved, see [`Circuit::try_update`].
+    pub fn update(&mut self, delta_time: T) {
+        self.try_update(delta_time).unwrap();
+    }
+
+    /// Advances the circuit by `delta_time` in steps of [`TransientOptions::max_step`],
+    /// the time left until the next step is only used to solve the circuit without
+    /// changing the elements' state. A step exceeding [`TransientOptions::tolerance`] is
+    /// undone with [`Conductor::restore`] and redone in twice as many substeps.
+    /// On error the time advances only by the completed substeps and the rest
+    /// of `delta_time` is dropped.
+    /// The [`Recorder`] takes a sample after a successful update.
+    pub fn try_update(&mut self, delta_time: T) -> Result<SolveReport, SolveError> {
+        let TransientOptions {
+            method,
+            max_step,
+            tolerance,
+        } = self.transient;
+
+        self.pending += delta_time;
+
+        let mut report = None;
+
+        while self.pending >= max_step {
+            for element in self.elements.values_mut() {
+                element.conductor.borrow_mut().save();
+            }
+
+            let substep = max_step / nalgebra::convert(self.substeps as f64);
+            let mut error = T::zero();
+
+            for completed in 0..self.substeps {
+                match self.step(substep, method, true) {
+                    Ok(step_report) => report = Some(step_report),
+                    Err(err) => {
+                        self.ticks += (completed * MAX_SUBSTEPS / self.substeps) as u64;
+                        self.pending = T::zero();
+                        return Err(err);
+                    }
+                }
+
+                let errors = self.elements.values().filter_map(|element| {
+                    element.conductor.borrow().truncation_error()
+                });
+
+                error = errors.fold(error, T::max);
+            }
+
+            if error > tolerance && self.substeps < MAX_SUBSTEPS {
+                for element in self.elements.values_mut() {
+                    element.conductor.borrow_mut().restore();
+                }
+
+                self.substeps *= 2;
+                continue;
+            }
 
-        for (i, id) in self.ids.iter().enumerate() {
-            let conductor = self.elements[id].conductor.borrow();
+            self.ticks += MAX_SUBSTEPS as u64;
+            self.pending -= max_step;
 
-            self.resistances[(i, i)] = conductor.resistance();
-            self.emf[(i, 0)] = conductor.emf();
+            if error * nalgebra::convert(16.0) < tolerance && self.substeps > 1 {
+                self.substeps /= 2;
+            }
         }
 
-        let lhs = loops * &self.resistances * loops_transposed;
-        let rhs = loops * &self.emf;
+        let report = match report {
+            Some(report) if self.pending.is_zero() => report,
+            _ => {
+                // the state can't be looked at in no time, reactive elements would
+                // force their values, so the shortest substep is taken instead
+                let delta_time = if self.pending.is_zero() {
+                    max_step / nalgebra::convert(MAX_SUBSTEPS as f64)
+                } else {
+                    self.pending
+                };
 
-        let loop_currents = lhs.qr().solve(&rhs).unwrap();
-        let edge_currents = loops_transposed * loop_currents;
+                self.step(delta_time, method, false).inspect_err(|_| {
+                    self.pending = T::zero();
+                })?
+            }
+        };
 
-        for (i, &id) in self.ids.iter().enumerate() {
-            let current = edge_currents[i];
-            let conductor = self.elements.get_mut(&id).unwrap().conductor.borrow_mut();
+        if !self.recorder.probes().is_empty() {
+            let readings = self
+                .recorder
+                .probes()
+                .iter()
+                .map(|&probe| self.measure(probe))
+                .collect();
 
-            conductor.zap(current, delta_time)
+            self.recorder.push(self.time() + self.pending, readings);
         }
+
+        Ok(report)
     }
 
-    pub fn add(&mut self, endpoints: [N; 2], conductor: C) -> ElementId {
-        let endpoints = endpoints.map(|weight| {
-            self.nodes
-                .get_by_right(&weight)
-                .copied()
-                .unwrap_or_else(|| self.add_node(weight))
-        });
+    /// Time of the last step which changed the elements' state.
+    pub fn time(&self) -> T {
+        let tick = self.transient.max_step / nalgebra::convert(MAX_SUBSTEPS as f64);
 
-        self.graph.add_edge(endpoints);
+        self.start + nalgebra::convert::<_, T>(self.ticks as f64) * tick
+    }
 
-        let id = ElementId(self.ids_count);
+    pub fn recorder(&self) -> &Recorder<N, T> {
+        &self.recorder
+    }
 
-        self.ids_count += 1;
-        self.ids.push(id);
+    pub fn recorder_mut(&mut self) -> &mut Recorder<N, T> {
+        &mut self.recorder
+    }
 
-        let element = CircuitElement::new(endpoints, conductor);
+    pub fn transient_options(&self) -> TransientOptions<T> {
+        self.transient
+    }
 
-        self.elements.insert(id, element);
+    /// Fails if `max_step` isn't positive and finite.
+    pub fn set_transient_options(
+        &mut self,
+        options: TransientOptions<T>,
+    ) -> Result<(), SolveError> {
+        if !options.max_step.is_finite() || options.max_step <= T::zero() {
+            return Err(SolveError::InvalidStep);
+        }
 
-        self.resize_matrices();
+        self.start = self.time();
+        self.ticks = 0;
+        self.transient = options;
 
-        id
+        Ok(())
+    }
+
+    pub fn newton_options(&self) -> NewtonOptions<T> {
+        self.newton
+    }
+
+    pub fn set_newton_options(&mut self, options: NewtonOptions<T>) {
+        self.newton = options;
+    }
+
+    /// Solves the circuit for a step of `delta_time`, the elements are zapped
+    /// with their currents if `commit` is set.
+    fn step(
+        &mut self,
+        delta_time: T,
+        method: IntegrationMethod,
+        commit: bool,
+    ) -> Result<SolveReport, SolveError> {
+        for element in self.elements.values_mut() {
+            element.conductor.borrow_mut().prepare(delta_time, method);
+        }
+
+        let (branch_ids, stamp_ids) = self.partition_ids();
+
+        // nonlinear elements are linearized at the voltages of the previous iteration
+        let mut voltages: Vec<_> = branch_ids
+            .iter()
+            .map(|id| self.elements[id].state.voltage)
+            .collect();
+
+        let mut potentials: Vec<_> = stamp_ids
+            .iter()
+            .map(|id| self.elements[id].potentials.clone())
+            .collect();
+
+        let (solution, parts, iterations) = self.solve_newton(
+            [&branch_ids, &stamp_ids],
+            &mut voltages,
+            &mut potentials,
+            |_, conductor| {
+                (
+                    conductor.emf(),
+                    conductor.resistance(),
+                    conductor.amperage(),
+                )
+            },
+        )?;
+
+        self.parts = parts;
+        self.potentials = solution.potentials.iter().copied().collect();
+
+        for (i, &id) in branch_ids.iter().enumerate() {
+            let current = solution.currents[i];
+            let element = self.elements.get_mut(&id).unwrap();
+
+            let [a, b] = [0, 1].map(|terminal| self.potentials[element.terminals[terminal].0]);
+            let voltage = a - b;
+
+            element.state = ElementState {
+                current,
+                voltage,
+                power: voltage * current,
+            };
+
+            if commit {
+                element.conductor.borrow_mut().zap(current, delta_time)
+            }
+        }
+
+        for (j, &id) in stamp_ids.iter().enumerate() {
+            let currents = &solution.terminal_currents[j];
+            let element = self.elements.get_mut(&id).unwrap();
+
+            let potentials: Vec<_> = element
+                .terminals
+                .iter()
+                .map(|idx| self.potentials[idx.0])
+                .collect();
+
+            let power = Iterator::zip(potentials.iter(), currents)
+                .fold(T::zero(), |power, (&potential, &current)| {
+                    power + potential * current
+                });
+
+            element.state = ElementState {
+                current: currents[0],
+                voltage: potentials[0] - potentials[1],
+                power,
+            };
+
+            element.potentials = potentials;
+            element.terminal_currents.clone_from(currents);
+
+            if commit {
+                element.conductor.borrow_mut().zap(currents[0], delta_time)
+            }
+        }
+
+        Ok(SolveReport {
+            unknowns: solution.unknowns,
+            iterations,
+        })
+    }
+
+    /// Solves the circuit with Newton–Raphson iterations starting at the `voltages` of
+    /// two-terminal elements and the `potentials` of multi-terminal ones in the order of
+    /// [`Circuit::partition_ids`], returns the solution, the connected parts of nodes and
+    /// the count of iterations. `linear` gives the emf, the resistance and the amperage
+    /// of an element, the first two are ignored for nonlinear elements.
+    fn solve_newton(
+        &mut self,
+        [branch_ids, stamp_ids]: [&[ElementId]; 2],
+        voltages: &mut [T],
+        potentials: &mut [Vec<T>],
+        linear: impl Fn(ElementId, &dyn Conductor<T>) -> (T, T, Option<T>),
+    ) -> Result<(Solution<T>, Vec<usize>, usize), SolveError> {
+        let mut iterations = 0;
+
+        let (network, solution) = loop {
+            iterations += 1;
+
+            let mut nonlinear = false;
+
+            let branches = Iterator::zip(branch_ids.iter(), voltages.iter())
+                .map(|(&id, &voltage)| {
+                    let element = &self.elements[&id];
+                    let conductor = element.conductor.borrow();
+
+                    let (emf, resistance, amperage) = linear(id, conductor);
+
+                    let (emf, resistance) = match conductor.linearize(voltage) {
+                        Some((conductance, current)) => {
+                            nonlinear = true;
+
+                            (current / conductance - voltage, conductance.recip())
+                        }
+                        None => (emf, resistance),
+                    };
+
+                    Branch {
+                        id,
+                        endpoints: [element.terminals[0].0, element.terminals[1].0],
+                        emf,
+                        resistance,
+                        amperage,
+                    }
+                })
+                .collect();
+
+            let stamps = Iterator::zip(stamp_ids.iter(), potentials.iter())
+                .map(|(&id, potentials)| {
+                    let element = &self.elements[&id];
+                    let nodes = element.terminals.iter().map(|idx| idx.0).collect();
+
+                    let mut stamp = Stamp::new(id, nodes, potentials.clone());
+
+                    element.conductor.borrow().stamp(&mut stamp);
+
+                    nonlinear |= stamp.is_nonlinear();
+
+                    stamp
+                })
+                .collect();
+
+            let network = Network {
+                nodes_count: self.nodes.len(),
+                branches,
+                stamps,
+                grounds: self.ground_indices(),
+                graph: &self.graph,
+            };
+
+            let solution = self.solver.solve(&network)?;
+
+            let values = Iterator::chain(solution.currents.iter(), solution.potentials.iter());
+
+            if !values.into_iter().all(|value| value.is_finite()) {
+                return Err(SolveError::Singular { cycle: vec![] });
+            }
+
+            if !nonlinear {
+                break (network, solution);
+            }
+
+            let mut converged = true;
+
+            let mut check = |new: T, old: T| {
+                let tolerance = self.newton.tolerance * (T::one() + new.abs());
+
+                converged &= (new - old).abs() <= tolerance;
+            };
+
+            for (voltage, branch) in Iterator::zip(voltages.iter_mut(), network.branches()) {
+                let [a, b] = branch.endpoints.map(|node| solution.potentials[node]);
+                let new_voltage = a - b;
+
+                check(new_voltage, *voltage);
+
+                let conductor = self.elements[&branch.id].conductor.borrow();
+                *voltage = conductor.limit(new_voltage, *voltage);
+            }
+
+            for (potentials, stamp) in Iterator::zip(potentials.iter_mut(), network.stamps()) {
+                let mut new_potentials: Vec<_> = stamp
+                    .nodes()
+                    .iter()
+                    .map(|&node| solution.potentials[node])
+                    .collect();
+
+                for (&new, &old) in Iterator::zip(new_potentials.iter(), potentials.iter()) {
+                    check(new, old);
+                }
+
+                let conductor = self.elements[&stamp.id()].conductor.borrow();
+                conductor.limit_potentials(&mut new_potentials, potentials);
+
+                *potentials = new_potentials;
+            }
+
+            if converged {
+                break (network, solution);
+            }
+
+            if iterations >= self.newton.max_iterations {
+                return Err(SolveError::NotConverged { iterations });
+            }
+        };
+
+        let mut parts = network.parts();
+
+        let parts = (0..network.nodes_count())
+            .map(|node| parts.find(node))
+            .collect();
+
+        Ok((solution, parts, iterations))
+    }
+
+    /// Panics if the circuit can't be solved, see [`Circuit::try_ac_analysis`].
+    pub fn ac_analysis(&self, frequency: T) -> AcSolution<N, T> {
+        self.try_ac_analysis(frequency).unwrap()
+    }
+
+    /// Solves the circuit driven by its sources at `frequency` with the elements described by
+    /// [`Conductor::ac_model`], nonlinear elements are linearized at the last update.
+    /// Multi-terminal elements are linearized too but don't drive the circuit.
+    pub fn try_ac_analysis(&self, frequency: T) -> Result<AcSolution<N, T>, SolveError> {
+        self.solve_ac(frequency, None)
+    }
+
+    /// Panics if the circuit can't be solved, see [`Circuit::try_frequency_sweep`].
+    pub fn frequency_sweep(
+        &self,
+        range: RangeInclusive<T>,
+        points: usize,
+        scale: SweepScale,
+        input: ElementId,
+        output: N,
+    ) -> Vec<BodePoint<T>> {
+        self.try_frequency_sweep(range, points, scale, input, output)
+            .unwrap()
+    }
+
+    /// Transfer function from the two-terminal element `input` to the voltage of the node
+    /// `output` at `points` frequencies of the range: the emf or the amperage of the input
+    /// is replaced by one and the other sources are turned off.
+    pub fn try_frequency_sweep(
+        &self,
+        range: RangeInclusive<T>,
+        points: usize,
+        scale: SweepScale,
+        input: ElementId,
+        output: N,
+    ) -> Result<Vec<BodePoint<T>>, SolveError> {
+        if self.element(input)?.edge.is_none() {
+            return Err(SolveError::Unsupported(input));
+        }
+
+        if scale == SweepScale::Log && (*range.start() <= T::zero() || *range.end() <= T::zero()) {
+            return Err(SolveError::InvalidRange);
+        }
+
+        scale
+            .frequencies(range, points)
+            .map(|frequency| {
+                let solution = self.solve_ac(frequency, Some(input))?;
+                let transfer = solution.voltage(output).ok_or(SolveError::Ungrounded)?;
+
+                Ok(BodePoint::new(frequency, transfer))
+            })
+            .collect()
+    }
+
+    /// Panics if the circuit can't be solved, see [`Circuit::try_dc_sweep`].
+    pub fn dc_sweep(
+        &mut self,
+        element: ElementId,
+        parameter: SweepParameter,
+        start: T,
+        stop: T,
+        steps: usize,
+        probes: &[Probe<N>],
+    ) -> Vec<DcPoint<T>> {
+        self.try_dc_sweep(element, parameter, start, stop, steps, probes)
+            .unwrap()
+    }
+
+    /// Solves the circuit at `steps + 1` values of the `parameter` of the two-terminal `element`
+    /// from `start` to `stop` and reads the `probes` at each of them. Elements are described
+    /// by [`Conductor::ac_model`] at zero frequency, so capacitors are open and inductors
+    /// are shorted, and the state of the circuit doesn't change.
+    pub fn try_dc_sweep(
+        &mut self,
+        element: ElementId,
+        parameter: SweepParameter,
+        start: T,
+        stop: T,
+        steps: usize,
+        probes: &[Probe<N>],
+    ) -> Result<Vec<DcPoint<T>>, SolveError> {
+        let dc = |conductor: &dyn Conductor<T>| match conductor.ac_model(T::zero(), T::zero()) {
+            AcModel::Impedance { emf, impedance } => (emf.re, impedance.re, None),
+            AcModel::Current(amperage) => (T::zero(), T::zero(), Some(amperage.re)),
+        };
+
+        let swept = self.element(element)?;
+        let conductor = swept.conductor.borrow();
+
+        let supported = swept.edge.is_some()
+            && conductor.linearize(T::zero()).is_none()
+            && match (parameter, dc(conductor).2) {
+                (SweepParameter::Amperage, amperage) => amperage.is_some(),
+                (_, amperage) => amperage.is_none(),
+            };
+
+        if !supported {
+            return Err(SolveError::Unsupported(element));
+        }
+
+        let (branch_ids, stamp_ids) = self.partition_ids();
+
+        // every point starts at the solution of the previous one
+        let mut voltages: Vec<_> = branch_ids
+            .iter()
+            .map(|id| self.elements[id].state.voltage)
+            .collect();
+
+        let mut potentials: Vec<_> = stamp_ids
+            .iter()
+            .map(|id| self.elements[id].potentials.clone())
+            .collect();
+
+        let intervals = nalgebra::convert::<_, T>(steps.max(1) as f64);
+
+        (0..=steps)
+            .map(|step| {
+                let value = start + (stop - start) * nalgebra::convert(step as f64) / intervals;
+
+                let (solution, parts, _) = self.solve_newton(
+                    [&branch_ids, &stamp_ids],
+                    &mut voltages,
+                    &mut potentials,
+                    |id, conductor| {
+                        let (emf, resistance, amperage) = dc(conductor);
+
+                        match parameter {
+                            _ if id != element => (emf, resistance, amperage),
+                            SweepParameter::Emf => (value, resistance, amperage),
+                            SweepParameter::Resistance => (emf, value, amperage),
+                            SweepParameter::Amperage => (emf, resistance, Some(value)),
+                        }
+                    },
+                )?;
+
+                let readings = probes
+                    .iter()
+                    .map(|&probe| self.read(probe, [&branch_ids, &stamp_ids], &solution, &parts))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(DcPoint { value, readings })
+            })
+            .collect()
+    }
+
+    /// Reads the probe from a solution of [`Circuit::solve_newton`].
+    fn read(
+        &self,
+        probe: Probe<N>,
+        [branch_ids, stamp_ids]: [&[ElementId]; 2],
+        solution: &Solution<T>,
+        parts: &[usize],
+    ) -> Result<T, SolveError> {
+        match probe {
+            Probe::Voltage(node) => {
+                let [node, ground] = [Some(node), self.ground()]
+                    .map(|node| Some(self.nodes.get_by_right(&node?)?.0));
+
+                match (node, ground) {
+                    (Some(node), Some(ground)) if parts[node] == parts[ground] => {
+                        Ok(solution.potentials[node] - solution.potentials[ground])
+                    }
+                    _ => Err(SolveError::Ungrounded),
+                }
+            }
+            Probe::Current(id) => {
+                if let Some(i) = branch_ids.iter().position(|&other| other == id) {
+                    Ok(solution.currents[i])
+                } else if let Some(j) = stamp_ids.iter().position(|&other| other == id) {
+                    Ok(solution.terminal_currents[j][0])
+                } else {
+                    Err(SolveError::UnknownElement(id))
+                }
+            }
+            Probe::Power(id) => {
+                let terminals = &self.element(id)?.terminals;
+                let potential = |terminal: usize| solution.potentials[terminals[terminal].0];
+
+                if let Some(i) = branch_ids.iter().position(|&other| other == id) {
+                    Ok((potential(0) - potential(1)) * solution.currents[i])
+                } else if let Some(j) = stamp_ids.iter().position(|&other| other == id) {
+                    let currents = &solution.terminal_currents[j];
+
+                    Ok((0..terminals.len())
+                        .fold(T::zero(), |power, k| power + potential(k) * currents[k]))
+                } else {
+                    Err(SolveError::UnknownElement(id))
+                }
+            }
+        }
+    }
+
+    /// Only the `input` drives the circuit with the unit emf or amperage if it's set.
+    fn solve_ac(
+        &self,
+        frequency: T,
+        input: Option<ElementId>,
+    ) -> Result<AcSolution<N, T>, SolveError> {
+        let angular_frequency = T::two_pi() * frequency;
+
+        let (branch_ids, stamp_ids) = self.partition_ids();
+
+        let branches = branch_ids
+            .iter()
+            .map(|&id| {
+                let element = &self.elements[&id];
+                let conductor = element.conductor.borrow();
+
+                let model = conductor.ac_model(angular_frequency, element.state.voltage);
+
+                let model = match input {
+                    Some(input) if input == id => model.with_excitation(Complex::from(T::one())),
+                    Some(_) => model.with_excitation(Complex::from(T::zero())),
+                    None => model,
+                };
+
+                AcBranch {
+                    id,
+                    endpoints: [element.terminals[0].0, element.terminals[1].0],
+                    model,
+                }
+            })
+            .collect();
+
+        let stamps = stamp_ids
+            .iter()
+            .map(|&id| {
+                let element = &self.elements[&id];
+                let nodes = element.terminals.iter().map(|idx| idx.0).collect();
+
+                let mut stamp = Stamp::new(id, nodes, element.potentials.clone());
+
+                element.conductor.borrow().stamp(&mut stamp);
+
+                stamp
+            })
+            .collect();
+
+        let network = AcNetwork {
+            nodes_count: self.nodes.len(),
+            branches,
+            stamps,
+            grounds: self.ground_indices(),
+        };
+
+        let nodes = self.nodes.iter().map(|(idx, &weight)| (idx.0, weight));
+
+        network.solve(frequency, self.ground(), nodes)
+    }
+
+    /// Current, voltage drop and consumed power of the element after the last update.
+    pub fn operating_point(&self, id: ElementId) -> ElementState<T> {
+        self.try_operating_point(id).unwrap()
+    }
+
+    pub fn try_operating_point(&self, id: ElementId) -> Result<ElementState<T>, SolveError> {
+        self.element(id).map(|element| element.state)
+    }
+
+    /// Sets the node the voltages are measured relative to, it's also the reference
+    /// of elements like operational amplifiers whose output current returns to the ground.
+    /// Several grounds are joined into one net as if they were connected by a wire.
+    pub fn set_ground(&mut self, node: N) {
+        if !self.grounds.contains(&node) {
+            self.grounds.push(node);
+        }
+    }
+
+    pub fn remove_ground(&mut self, node: N) {
+        self.grounds.retain(|&ground| ground != node);
+    }
+
+    /// The ground nodes in the order they were set, including the ones without elements.
+    pub fn grounds(&self) -> &[N] {
+        &self.grounds
+    }
+
+    /// The first ground with elements connected to it, the voltages are measured relative to it.
+    pub fn ground(&self) -> Option<N> {
+        self.grounds
+            .iter()
+            .copied()
+            .find(|ground| self.nodes.contains_right(ground))
+    }
+
+    /// Potential of the node relative to the ground after the last update,
+    /// `None` if there is no ground or the node isn't connected to it.
+    pub fn voltage(&self, node: N) -> Option<T> {
+        self.potential_difference(node, self.ground()?)
+    }
+
+    /// Reading of the probe after the last update, `None` if it can't be measured.
+    pub fn measure(&self, probe: Probe<N>) -> Option<T> {
+        match probe {
+            Probe::Voltage(node) => self.voltage(node),
+            Probe::Current(id) => Some(self.elements.get(&id)?.state.current),
+            Probe::Power(id) => Some(self.elements.get(&id)?.state.power),
+        }
+    }
+
+    /// `φ(a) - φ(b)` after the last update, `None` if the nodes aren't connected.
+    pub fn potential_difference(&self, a: N, b: N) -> Option<T> {
+        let a = self.nodes.get_by_right(&a)?.0;
+        let b = self.nodes.get_by_right(&b)?.0;
+
+        (self.parts.get(a)? == self.parts.get(b)?).then(|| self.potentials[a] - self.potentials[b])
+    }
+
+    pub fn add(&mut self, endpoints: [N; 2], conductor: C) -> ElementId {
+        let endpoints = endpoints.map(|weight| self.node(weight));
+
+        let edge = self.graph.add_edge(endpoints);
+
+        self.insert(Some(edge), endpoints.to_vec(), conductor)
+    }
+
+    /// Adds an element with any count of terminals, it's stamped into the system
+    /// with [`Conductor::stamp`], so only solvers supporting stamps can solve it.
+    /// Panics if there are less than two terminals.
+    pub fn add_multi(&mut self, terminals: &[N], conductor: C) -> ElementId {
+        assert!(
+            terminals.len() >= 2,
+            "an element needs at least two terminals"
+        );
+
+        let terminals = terminals.iter().map(|&weight| self.node(weight)).collect();
+
+        self.insert(None, terminals, conductor)
     }
 
     pub fn change(&mut self, id: ElementId, new_endpoints: [N; 2]) {
-        let new_endpoints = new_endpoints.map(|weight| {
-            self.nodes
-                .get_by_right(&weight)
-                .copied()
-                .unwrap_or_else(|| self.add_node(weight))
+        self.try_change(id, new_endpoints).unwrap();
+    }
+
+    pub fn try_change(&mut self, id: ElementId, new_endpoints: [N; 2]) -> Result<(), SolveError> {
+        self.try_change_multi(id, &new_endpoints)
+    }
+
+    pub fn change_multi(&mut self, id: ElementId, new_terminals: &[N]) {
+        self.try_change_multi(id, new_terminals).unwrap();
+    }
+
+    /// The count of terminals can't change.
+    pub fn try_change_multi(
+        &mut self,
+        id: ElementId,
+        new_terminals: &[N],
+    ) -> Result<(), SolveError> {
+        let element = self.element(id)?;
+
+        if element.terminals.len() != new_terminals.len() {
+            return Err(SolveError::TerminalsMismatch {
+                id,
+                expected: element.terminals.len(),
+            });
+        }
+
+        let unchanged = element
+            .terminals
+            .iter()
+            .zip(new_terminals)
+            .all(|(node, weight)| self.nodes.get_by_left(node) == Some(weight));
+
+        if unchanged {
+            return Ok(());
+        }
+
+        let old_edge = element.edge;
+
+        let new_terminals: Vec<_> = new_terminals
+            .iter()
+            .map(|&weight| self.node(weight))
+            .collect();
+
+        let new_edge = old_edge.map(|old_edge| {
+            self.graph.remove_edge(old_edge);
+            self.graph.add_edge([new_terminals[0], new_terminals[1]])
         });
 
-        let old_endpoints = self.elements[&id].endpoints;
+        let element = self.element_mut(id)?;
 
-        self.elements.get_mut(&id).unwrap().endpoints = new_endpoints;
+        element.edge = new_edge;
+        element.terminals = new_terminals;
 
         self.ids.retain(|&other_id| other_id != id);
         self.ids.push(id);
 
-        self.graph.remove_edge(old_endpoints);
-        self.graph.add_edge(new_endpoints);
+        Ok(())
     }
 
     pub fn remove(&mut self, id: ElementId) {
-        let element = self.elements.remove(&id).unwrap();
-        let edge = element.endpoints;
+        self.try_remove(id).unwrap();
+    }
+
+    /// Fails if the element controls another one, see [`Conductor::control`].
+    pub fn try_remove(&mut self, id: ElementId) -> Result<(), SolveError> {
+        let dependent = self
+            .elements
+            .iter()
+            .find(|(_, element)| element.conductor.borrow().control() == Some(id));
+
+        if let Some((&dependent, _)) = dependent {
+            return Err(SolveError::Controlling { id, dependent });
+        }
+
+        let element = self
+            .elements
+            .remove(&id)
+            .ok_or(SolveError::UnknownElement(id))?;
 
         self.ids.retain(|&other_id| other_id != id);
-        self.resize_matrices();
 
-        self.graph.remove_edge(edge);
+        if let Some(edge) = element.edge {
+            self.graph.remove_edge(edge);
+        }
+
+        Ok(())
     }
 
-    pub fn iter<'a: 'data>(&'a self) -> impl Iterator<Item = (ElementId, &C)> + 'data {
+    pub fn iter<'a: 'data>(&'a self) -> impl Iterator<Item = (ElementId, &'a C)> + 'data {
         self.elements
             .iter()
             .map(move |(&idx, element)| (idx, &element.conductor))
     }
 
+    /// The first two terminals of a multi-terminal element.
     pub fn endpoints(&self, id: ElementId) -> [N; 2] {
-        self.elements
-            .get(&id)
-            .unwrap()
-            .endpoints
-            .map(|idx| *self.nodes.get_by_left(&idx).unwrap())
+        self.try_endpoints(id).unwrap()
+    }
+
+    pub fn try_endpoints(&self, id: ElementId) -> Result<[N; 2], SolveError> {
+        let terminals = &self.element(id)?.terminals;
+
+        Ok([0, 1].map(|terminal| *self.nodes.get_by_left(&terminals[terminal]).unwrap()))
+    }
+
+    pub fn terminals(&self, id: ElementId) -> Vec<N> {
+        self.try_terminals(id).unwrap()
+    }
+
+    pub fn try_terminals(&self, id: ElementId) -> Result<Vec<N>, SolveError> {
+        let terminals = &self.element(id)?.terminals;
+
+        Ok(terminals
+            .iter()
+            .map(|idx| *self.nodes.get_by_left(idx).unwrap())
+            .collect())
+    }
+
+    /// Currents flowing into the terminals of a multi-terminal element after the last update,
+    /// empty for two-terminal elements.
+    pub fn terminal_currents(&self, id: ElementId) -> Vec<T> {
+        self.try_terminal_currents(id).unwrap()
+    }
+
+    pub fn try_terminal_currents(&self, id: ElementId) -> Result<Vec<T>, SolveError> {
+        self.element(id)
+            .map(|element| element.terminal_currents.clone())
     }
 
     pub fn get_mut(&mut self, id: ElementId) -> &mut C {
-        &mut self.elements.get_mut(&id).unwrap().conductor
+        self.try_get_mut(id).unwrap()
+    }
+
+    pub fn try_get_mut(&mut self, id: ElementId) -> Result<&mut C, SolveError> {
+        self.element_mut(id).map(|element| &mut element.conductor)
+    }
+
+    pub fn set_solver(&mut self, solver: impl Solver<T> + 'static) {
+        self.solver = Box::new(solver);
+    }
+
+    /// Indices of the ground nodes with elements connected to them.
+    fn ground_indices(&self) -> Vec<usize> {
+        self.grounds
+            .iter()
+            .filter_map(|ground| self.nodes.get_by_right(ground))
+            .map(|idx| idx.0)
+            .collect()
+    }
+
+    /// Ids of two-terminal elements in the order of the graph's edges and
+    /// ids of multi-terminal elements.
+    fn partition_ids(&self) -> (Vec<ElementId>, Vec<ElementId>) {
+        self.ids
+            .iter()
+            .partition(|id| self.elements[id].edge.is_some())
+    }
+
+    fn element(&self, id: ElementId) -> Result<&CircuitElement<'data, C, T>, SolveError> {
+        self.elements.get(&id).ok_or(SolveError::UnknownElement(id))
+    }
+
+    fn element_mut(
+        &mut self,
+        id: ElementId,
+    ) -> Result<&mut CircuitElement<'data, C, T>, SolveError> {
+        self.elements
+            .get_mut(&id)
+            .ok_or(SolveError::UnknownElement(id))
+    }
+
+    fn insert(
+        &mut self,
+        edge: Option<EdgeIndex>,
+        terminals: Vec<NodeIndex>,
+        conductor: C,
+    ) -> ElementId {
+        let id = ElementId(self.ids_count);
+
+        self.ids_count += 1;
+        self.ids.push(id);
+
+        let element = CircuitElement::new(edge, terminals, conductor);
+
+        self.elements.insert(id, element);
+
+        id
+    }
+
+    fn node(&mut self, weight: N) -> NodeIndex {
+        self.nodes
+            .get_by_right(&weight)
+            .copied()
+            .unwrap_or_else(|| self.add_node(weight))
     }
 
     fn add_node(&mut self, weight: N) -> NodeIndex {
@@ -140,27 +981,30 @@ where
 
         new_idx
     }
-
-    fn resize_matrices(&mut self) {
-        let size = self.ids.len();
-
-        self.resistances = DMatrix::from_element(size, size, 0.0);
-        self.emf = DMatrix::from_element(size, 1, 0.0);
-    }
 }
 
-impl<'data, C, N> Default for Circuit<'data, C, N>
+impl<'data, C, N, T> Default for Circuit<'data, C, N, T>
 where
-    C: BorrowMut<dyn Conductor + 'data>,
+    C: BorrowMut<dyn Conductor<T> + 'data>,
     N: Copy + Hash + Eq + Default,
+    T: RealField + Copy,
 {
     fn default() -> Self {
         Self {
             graph: Default::default(),
             elements: Default::default(),
             nodes: Default::default(),
-            resistances: Default::default(),
-            emf: Default::default(),
+            solver: Box::new(MnaSolver),
+            transient: TransientOptions::default(),
+            newton: NewtonOptions::default(),
+            start: T::zero(),
+            ticks: 0,
+            pending: T::zero(),
+            substeps: 1,
+            grounds: Default::default(),
+            potentials: Default::default(),
+            parts: Default::default(),
+            recorder: Default::default(),
             lt: Default::default(),
             ids: Default::default(),
             ids_count: Default::default(),
@@ -168,21 +1012,69 @@ where
     }
 }
 
-struct CircuitElement<'data, C: BorrowMut<dyn Conductor + 'data>> {
-    pub endpoints: [NodeIndex; 2],
+struct CircuitElement<'data, C, T>
+where
+    C: BorrowMut<dyn Conductor<T> + 'data>,
+    T: RealField + Copy,
+{
+    /// Only two-terminal elements added with [`Circuit::add`] are edges of the graph.
+    pub edge: Option<EdgeIndex>,
+    pub terminals: Vec<NodeIndex>,
     pub conductor: C,
+    pub state: ElementState<T>,
+    pub potentials: Vec<T>,
+    pub terminal_currents: Vec<T>,
     lt: PhantomData<&'data ()>,
 }
 
-impl<'data, C: BorrowMut<dyn Conductor + 'data>> CircuitElement<'data, C> {
-    pub fn new(endpoints: [NodeIndex; 2], conductor: C) -> Self {
+impl<'data, C, T> CircuitElement<'data, C, T>
+where
+    C: BorrowMut<dyn Conductor<T> + 'data>,
+    T: RealField + Copy,
+{
+    pub fn new(edge: Option<EdgeIndex>, terminals: Vec<NodeIndex>, conductor: C) -> Self {
+        let (potentials, terminal_currents) = match edge {
+            Some(_) => (vec![], vec![]),
+            None => (
+                vec![T::zero(); terminals.len()],
+                vec![T::zero(); terminals.len()],
+            ),
+        };
+
         Self {
-            endpoints,
+            edge,
+            terminals,
             conductor,
+            potentials,
+            terminal_currents,
+            state: ElementState {
+                current: T::zero(),
+                voltage: T::zero(),
+                power: T::zero(),
+            },
             lt: PhantomData,
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct ElementId(usize);
+
+/// The current flows from the first endpoint to the second one,
+/// `voltage` is the potential of the first endpoint minus the potential of the second one.
+/// For a multi-terminal element it's the current flowing into the first terminal and
+/// the consumed power of all the terminals.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct ElementState<T = f32> {
+    pub current: T,
+    pub voltage: T,
+    pub power: T,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SolveReport {
+    /// Size of the solved linear system.
+    pub unknowns: usize,
+    /// Newton–Raphson iterations, a circuit without nonlinear elements is solved at once.
+    pub iterations: usize,
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+
+use nalgebra::{Complex, ComplexField, RealField};
+
+use super::solver::{Branch, CsrMatrix, MnaSystem, Stamp};
+use super::{ElementId, SolveError};
+
+/// Phasor model of a two-terminal element for [`Circuit::ac_analysis`](crate::Circuit::ac_analysis),
+/// the current flows from the first endpoint to the second one like in
+/// [`Branch`](super::solver::Branch).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AcModel<T = f32> {
+    /// The drop across the element is `impedance * current - emf`, zero impedance
+    /// makes it an ideal voltage source or a wire.
+    Impedance {
+        emf: Complex<T>,
+        impedance: Complex<T>,
+    },
+    /// The current is forced like by an ideal current source.
+    Current(Complex<T>),
+}
+
+impl<T: RealField + Copy> AcModel<T> {
+    /// A passive element without an emf.
+    pub fn impedance(impedance: Complex<T>) -> Self {
+        Self::Impedance {
+            emf: Complex::from(T::zero()),
+            impedance,
+        }
+    }
+
+    /// The same element with its emf or amperage replaced.
+    pub(crate) fn with_excitation(self, excitation: Complex<T>) -> Self {
+        match self {
+            Self::Impedance { impedance, .. } => Self::Impedance {
+                emf: excitation,
+                impedance,
+            },
+            Self::Current(_) => Self::Current(excitation),
+        }
+    }
+}
+
+/// Spacing of the frequencies of [`Circuit::frequency_sweep`](crate::Circuit::frequency_sweep).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SweepScale {
+    Linear,
+    /// Equal ratios of neighbouring frequencies, the range must be positive.
+    Log,
+}
+
+impl SweepScale {
+    /// `points` frequencies from the start of the range to its end.
+    pub fn frequencies<T: RealField + Copy>(
+        self,
+        range: RangeInclusive<T>,
+        points: usize,
+    ) -> impl Iterator<Item = T> {
+        let (start, end) = range.into_inner();
+        let intervals = nalgebra::convert::<_, T>(points.saturating_sub(1).max(1) as f64);
+
+        (0..points).map(move |point| {
+            let t = nalgebra::convert::<_, T>(point as f64) / intervals;
+
+            match self {
+                SweepScale::Linear => start + (end - start) * t,
+                SweepScale::Log => start * (end / start).powf(t),
+            }
+        })
+    }
+}
+
+/// A point of a Bode plot: the gain in decibels and the phase in degrees of
+/// the transfer function at `frequency`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BodePoint<T = f32> {
+    pub frequency: T,
+    pub gain: T,
+    pub phase: T,
+}
+
+impl<T: RealField + Copy> BodePoint<T> {
+    pub fn new(frequency: T, transfer: Complex<T>) -> Self {
+        Self {
+            frequency,
+            gain: nalgebra::convert::<_, T>(20.0) * transfer.modulus().log10(),
+            phase: transfer.argument() * nalgebra::convert(180.0) / T::pi(),
+        }
+    }
+}
+
+/// Complex potentials and currents of a circuit driven by its sources at a single frequency,
+/// their magnitudes and phases are given by [`ComplexField::modulus`] and [`ComplexField::argument`].
+pub struct AcSolution<N, T = f32> {
+    frequency: T,
+    ground: Option<N>,
+    /// Potentials of nodes and the connected parts they belong to.
+    nodes: HashMap<N, (Complex<T>, usize)>,
+    currents: HashMap<ElementId, Complex<T>>,
+    terminal_currents: HashMap<ElementId, Vec<Complex<T>>>,
+}
+
+impl<N: Copy + Hash + Eq, T: RealField + Copy> AcSolution<N, T> {
+    pub fn frequency(&self) -> T {
+        self.frequency
+    }
+
+    /// Potential of the node relative to the ground, `None` if there is no ground
+    /// or the node isn't connected to it.
+    pub fn voltage(&self, node: N) -> Option<Complex<T>> {
+        self.potential_difference(node, self.ground?)
+    }
+
+    /// `φ(a) - φ(b)`, `None` if the nodes aren't connected.
+    pub fn potential_difference(&self, a: N, b: N) -> Option<Complex<T>> {
+        let (a, a_part) = self.nodes.get(&a)?;
+        let (b, b_part) = self.nodes.get(&b)?;
+
+        (a_part == b_part).then(|| a - b)
+    }
+
+    /// The current flowing from the first endpoint of the element to the second one
+    /// or into the first terminal of a multi-terminal element.
+    pub fn current(&self, id: ElementId) -> Option<Complex<T>> {
+        self.currents.get(&id).copied()
+    }
+
+    /// Currents flowing into the terminals of a multi-terminal element.
+    pub fn terminal_currents(&self, id: ElementId) -> Option<&[Complex<T>]> {
+        self.terminal_currents.get(&id).map(Vec::as_slice)
+    }
+}
+
+pub(crate) struct AcBranch<T> {
+    pub id: ElementId,
+    pub endpoints: [usize; 2],
+    pub model: AcModel<T>,
+}
+
+/// Snapshot of a circuit for the AC analysis, the stamps are only used as the matrix
+/// of the system, so multi-terminal elements don't drive the circuit.
+pub(crate) struct AcNetwork<T> {
+    pub nodes_count: usize,
+    pub branches: Vec<AcBranch<T>>,
+    pub stamps: Vec<Stamp<T>>,
+    pub grounds: Vec<usize>,
+}
+
+impl<T: RealField + Copy> AcNetwork<T> {
+    /// Solves the network with the modified nodal analysis in complex numbers,
+    /// `nodes` gives the weight of every node.
+    pub fn solve<N: Copy + Hash + Eq>(
+        &self,
+        frequency: T,
+        ground: Option<N>,
+        nodes: impl Iterator<Item = (usize, N)>,
+    ) -> Result<AcSolution<N, T>, SolveError> {
+        if self.grounds.is_empty() && self.stamps.iter().any(Stamp::is_grounded) {
+            return Err(SolveError::Ungrounded);
+        }
+
+        let zero = Complex::from(T::zero());
+
+        let branches: Vec<_> = self
+            .branches
+            .iter()
+            .map(|branch| {
+                let (emf, impedance, amperage) = match branch.model {
+                    AcModel::Impedance { emf, impedance } => (emf, impedance, None),
+                    AcModel::Current(amperage) => (zero, zero, Some(amperage)),
+                };
+
+                Branch {
+                    id: branch.id,
+                    endpoints: branch.endpoints,
+                    emf,
+                    resistance: impedance,
+                    amperage,
+                }
+            })
+            .collect();
+
+        let mut system = MnaSystem::new(
+            self.nodes_count,
+            &branches,
+            &self.stamps,
+            &self.grounds,
+            false,
+        );
+
+        let (entries, rhs) = system.assemble()?;
+
+        let solution = CsrMatrix::from_triplets(system.size(), entries)
+            .lu()
+            .ok_or(SolveError::Singular { cycle: vec![] })?
+            .solve(&rhs);
+
+        let potentials = system.potentials(&solution);
+
+        if !potentials.iter().all(|potential| potential.is_finite()) {
+            return Err(SolveError::Singular { cycle: vec![] });
+        }
+
+        let branch_currents = system.currents(&solution, &potentials)?;
+        let stamp_currents = system.terminal_currents(&solution);
+
+        let mut currents: HashMap<_, _> = Iterator::zip(branches.iter(), branch_currents.iter())
+            .map(|(branch, &current)| (branch.id, current))
+            .collect();
+
+        let mut terminal_currents = HashMap::new();
+
+        for (stamp, terminals) in Iterator::zip(self.stamps.iter(), stamp_currents) {
+            currents.insert(stamp.id(), terminals[0]);
+            terminal_currents.insert(stamp.id(), terminals);
+        }
+
+        let nodes = nodes
+            .map(|(idx, weight)| (weight, (potentials[idx], system.part(idx))))
+            .collect();
+
+        Ok(AcSolution {
+            frequency,
+            ground,
+            nodes,
+            currents,
+            terminal_currents,
+        })
+    }
+}
@@ -0,0 +1,47 @@
+#[derive(Default)]
+pub struct AdjacencyGraph {
+    adjacent: Vec<Vec<(EdgeIndex, NodeIndex)>>,
+}
+
+impl AdjacencyGraph {
+    /// Parallel edges are allowed, self-loops don't connect anything so they aren't stored.
+    pub fn add_edge(&mut self, edge: EdgeIndex, [a, b]: [NodeIndex; 2]) {
+        if a != b {
+            self.adjacent[a.0].push((edge, b));
+            self.adjacent[b.0].push((edge, a));
+        }
+    }
+
+    pub fn remove_edge(&mut self, edge: EdgeIndex, endpoints: [NodeIndex; 2]) {
+        for node in endpoints {
+            self.adjacent[node.0].retain(|&(other_edge, _)| other_edge != edge);
+        }
+    }
+
+    pub fn next_node(&mut self) -> NodeIndex {
+        self.adjacent.push(vec![]);
+
+        NodeIndex(self.nodes_count() - 1)
+    }
+
+    pub fn nodes_count(&self) -> usize {
+        self.adjacent.len()
+    }
+
+    pub fn neighbour_nodes(&self, node: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.incident_edges(node).map(|(_, other)| other)
+    }
+
+    pub fn incident_edges(
+        &self,
+        node: NodeIndex,
+    ) -> impl Iterator<Item = (EdgeIndex, NodeIndex)> + '_ {
+        self.adjacent[node.0].iter().copied()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeIndex(pub(super) usize);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeIndex(pub(super) usize);
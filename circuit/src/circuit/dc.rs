@@ -0,0 +1,16 @@
+/// A parameter of a two-terminal element varied by [`Circuit::dc_sweep`](crate::Circuit::dc_sweep).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SweepParameter {
+    Emf,
+    Resistance,
+    /// The current of an ideal current source.
+    Amperage,
+}
+
+/// Readings of the probes of [`Circuit::dc_sweep`](crate::Circuit::dc_sweep) in their order
+/// at a `value` of the swept parameter.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DcPoint<T = f32> {
+    pub value: T,
+    pub readings: Vec<T>,
+}
@@ -0,0 +1,33 @@
+pub struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    pub fn new(size: usize) -> Self {
+        Self {
+            parents: (0..size).collect(),
+        }
+    }
+
+    pub fn find(&mut self, mut idx: usize) -> usize {
+        while self.parents[idx] != idx {
+            self.parents[idx] = self.parents[self.parents[idx]];
+            idx = self.parents[idx];
+        }
+
+        idx
+    }
+
+    /// Joins the sets of both nodes, returns `false` if they were already joined.
+    pub fn union(&mut self, [a, b]: [usize; 2]) -> bool {
+        let roots = [self.find(a), self.find(b)];
+
+        if roots[0] == roots[1] {
+            false
+        } else {
+            self.parents[roots[1]] = roots[0];
+
+            true
+        }
+    }
+}
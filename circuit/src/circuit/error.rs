@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fmt;
+
+use super::ElementId;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SolveError {
+    /// The circuit has no unique solution. `cycle` is the loop causing it if it's known,
+    /// e.g. a loop of ideal wires for the loop current method.
+    Singular { cycle: Vec<ElementId> },
+    /// A source with zero resistance is closed by a loop of zero-resistance elements.
+    ShortedSource(ElementId),
+    /// The current of an ideal current source has nowhere to flow.
+    OpenSource(ElementId),
+    UnknownElement(ElementId),
+    /// The element can't be removed while it controls the `dependent` source.
+    Controlling { id: ElementId, dependent: ElementId },
+    /// The element can't be solved by the chosen solver or used by the analysis.
+    Unsupported(ElementId),
+    /// The element was given another count of terminals than it has.
+    TerminalsMismatch { id: ElementId, expected: usize },
+    /// There is no ground while an element passes its current to it
+    /// or the measured node isn't connected to the ground.
+    Ungrounded,
+    /// The Newton–Raphson iteration for nonlinear elements didn't converge.
+    NotConverged { iterations: usize },
+    /// The range of a logarithmic sweep isn't positive.
+    InvalidRange,
+    /// [`TransientOptions::max_step`](super::TransientOptions::max_step) isn't positive and finite.
+    InvalidStep,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Singular { cycle } if cycle.is_empty() => {
+                write!(f, "the circuit has no unique solution")
+            }
+            Self::Singular { cycle } => {
+                write!(f, "the circuit has no unique solution in the loop {cycle:?}")
+            }
+            Self::ShortedSource(id) => write!(f, "the source {id:?} is short-circuited"),
+            Self::OpenSource(id) => write!(f, "the current source {id:?} is open-circuited"),
+            Self::UnknownElement(id) => write!(f, "there is no element {id:?}"),
+            Self::Controlling { id, dependent } => {
+                write!(f, "the element {id:?} controls the source {dependent:?}")
+            }
+            Self::Unsupported(id) => write!(f, "the solver doesn't support the element {id:?}"),
+            Self::TerminalsMismatch { id, expected } => {
+                write!(f, "the element {id:?} has {expected} terminals")
+            }
+            Self::Ungrounded => write!(f, "the circuit isn't connected to the ground"),
+            Self::NotConverged { iterations } => {
+                write!(f, "the solution didn't converge in {iterations} iterations")
+            }
+            Self::InvalidRange => write!(f, "the logarithmic sweep range must be positive"),
+            Self::InvalidStep => write!(f, "the maximum step must be positive and finite"),
+        }
+    }
+}
+
+impl Error for SolveError {}
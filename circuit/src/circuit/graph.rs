@@ -1,90 +1,215 @@
-use std::mem;
+use std::cell::OnceCell;
+use std::collections::{BTreeSet, HashMap};
 
-use nalgebra::DMatrix;
+use nalgebra::{DMatrix, RealField};
 
-use super::matrix::{MatrixGraph, NodeIndex};
+use super::adjacency::{AdjacencyGraph, EdgeIndex, NodeIndex};
 use super::spanning_forest::SpanningForest;
 
-#[derive(Default)]
-pub struct CircuitGraph {
-    graph: MatrixGraph,
-    spanning: SpanningForest,
+type Cycle = Vec<(EdgeIndex, [NodeIndex; 2])>;
 
-    edges: Vec<[NodeIndex; 2]>,
+pub struct CircuitGraph<T> {
+    graph: AdjacencyGraph,
 
-    loops: DMatrix<f32>,
-    loops_transposed: DMatrix<f32>,
+    edges: Vec<(EdgeIndex, [NodeIndex; 2])>,
+    endpoints: HashMap<EdgeIndex, [NodeIndex; 2]>,
+    edges_count: usize,
+
+    /// Only the loop current method needs the fundamental cycles, so they're built
+    /// on the first request and maintained on changes from then on.
+    cycles: OnceCell<FundamentalCycles>,
+    loops: OnceCell<(DMatrix<T>, DMatrix<T>)>,
+}
+
+impl<T> Default for CircuitGraph<T> {
+    fn default() -> Self {
+        Self {
+            graph: AdjacencyGraph::default(),
+            edges: vec![],
+            endpoints: HashMap::new(),
+            edges_count: 0,
+            cycles: OnceCell::new(),
+            loops: OnceCell::new(),
+        }
+    }
 }
 
-impl CircuitGraph {
+impl<T: RealField + Copy> CircuitGraph<T> {
     pub fn next_node(&mut self) -> NodeIndex {
-        self.spanning.next_node();
+        if let Some(cycles) = self.cycles.get_mut() {
+            cycles.spanning.next_node();
+        }
+
         self.graph.next_node()
     }
 
-    pub fn add_edge(&mut self, endpoints: [NodeIndex; 2]) {
-        self.graph.add_edge(endpoints);
-        self.spanning.add_edge(endpoints);
+    pub fn add_edge(&mut self, endpoints: [NodeIndex; 2]) -> EdgeIndex {
+        let edge = EdgeIndex(self.edges_count);
+
+        self.edges_count += 1;
+
+        self.graph.add_edge(edge, endpoints);
+
+        self.edges.push((edge, endpoints));
+        self.endpoints.insert(edge, endpoints);
+
+        if let Some(cycles) = self.cycles.get_mut() {
+            cycles.add_edge(edge, endpoints);
+        }
 
-        self.edges.push(endpoints);
+        self.loops.take();
 
-        self.update_loops();
+        edge
     }
 
-    pub fn remove_edge(&mut self, endpoints: [NodeIndex; 2]) {
-        self.graph.remove_edge(endpoints);
+    pub fn remove_edge(&mut self, edge: EdgeIndex) {
+        let position = self
+            .edges
+            .iter()
+            .position(|&(other_edge, _)| other_edge == edge)
+            .unwrap();
+
+        self.edges.remove(position);
 
-        self.edges
-            .retain(|&other_endpoints| other_endpoints != endpoints);
+        let endpoints = self.endpoints.remove(&edge).unwrap();
 
-        self.spanning = SpanningForest::build(&self.graph);
+        self.graph.remove_edge(edge, endpoints);
+
+        if let Some(cycles) = self.cycles.get_mut() {
+            cycles.remove_edge(edge, endpoints);
+        }
 
-        self.update_loops()
+        self.loops.take();
     }
 
-    pub fn loops(&self) -> (&DMatrix<f32>, &DMatrix<f32>) {
-        (&self.loops, &self.loops_transposed)
+    pub fn loops(&self) -> (&DMatrix<T>, &DMatrix<T>) {
+        let (loops, loops_transposed) = self.loops.get_or_init(|| {
+            let loops = self.build_loops();
+            let loops_transposed = loops.transpose();
+
+            (loops, loops_transposed)
+        });
+
+        (loops, loops_transposed)
     }
 
-    fn update_loops(&mut self) {
-        self.loops = DMatrix::from_vec(0, self.edges.len(), vec![]);
+    fn build_loops(&self) -> DMatrix<T> {
+        let cycles = self.cycles.get_or_init(|| {
+            let mut cycles = FundamentalCycles::default();
 
-        let cycles = fundamental_cycles(&self.graph, &self.spanning).enumerate();
+            for _ in 0..self.graph.nodes_count() {
+                cycles.spanning.next_node();
+            }
 
-        for (i, cycle) in cycles {
-            self.loops = mem::take(&mut self.loops).insert_row(i, 0.0);
+            for &(edge, endpoints) in &self.edges {
+                cycles.add_edge(edge, endpoints);
+            }
+
+            cycles
+        });
+
+        let columns: HashMap<_, _> = self
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(j, &(edge, endpoints))| (edge, (j, endpoints)))
+            .collect();
 
-            for edge in cycle {
-                let mut edges = self.edges.iter().enumerate();
+        let rows = self
+            .edges
+            .iter()
+            .filter_map(|(edge, _)| cycles.cycles.get(edge));
 
-                let reversed_edge = [edge[1], edge[0]];
+        let mut loops = DMatrix::zeros(cycles.cycles.len(), self.edges.len());
 
-                let (j, direction) = edges
-                    .find_map(|(j, &other_edge)| {
-                        Option::or(
-                            (other_edge == edge).then_some((j, 1.0)),
-                            (other_edge == reversed_edge).then_some((j, -1.0)),
-                        )
-                    })
-                    .unwrap();
+        for (i, cycle) in rows.enumerate() {
+            for &(edge, traversed) in cycle {
+                let (j, endpoints) = columns[&edge];
 
-                self.loops[(i, j)] = direction;
+                loops[(i, j)] = if traversed == endpoints {
+                    T::one()
+                } else {
+                    -T::one()
+                };
             }
         }
 
-        self.loops_transposed = self.loops.transpose();
+        loops
     }
 }
 
-fn fundamental_cycles<'circuit>(
-    graph: &'circuit MatrixGraph,
-    spanning: &'circuit SpanningForest,
-) -> impl Iterator<Item = Vec<[NodeIndex; 2]>> + 'circuit {
-    let graph_xor_spanning = graph.edges().filter(|&edge| !spanning.has_edge(edge));
-
-    graph_xor_spanning.map(|endpoints| {
-        let mut path = spanning.find_path(endpoints);
-        path.push(endpoints);
-        path
-    })
+#[derive(Default)]
+struct FundamentalCycles {
+    spanning: SpanningForest,
+    /// Fundamental cycle of every edge out of the spanning forest.
+    cycles: HashMap<EdgeIndex, Cycle>,
+    /// Edges out of the spanning forest whose cycles pass through a forest edge.
+    passing: HashMap<EdgeIndex, BTreeSet<EdgeIndex>>,
+}
+
+impl FundamentalCycles {
+    fn add_edge(&mut self, edge: EdgeIndex, endpoints: [NodeIndex; 2]) {
+        if !self.spanning.add_edge(edge, endpoints) {
+            self.insert_cycle(edge, endpoints);
+        }
+    }
+
+    fn remove_edge(&mut self, edge: EdgeIndex, endpoints: [NodeIndex; 2]) {
+        if self.cycles.contains_key(&edge) {
+            self.remove_cycle(edge);
+        } else if self.spanning.remove_edge(edge, endpoints) {
+            self.reconnect(edge);
+        }
+    }
+
+    /// Joins the parts of the tree split by the removed edge with an edge out of the forest
+    /// whose cycle passed through the removed one, if there is such an edge.
+    fn reconnect(&mut self, removed: EdgeIndex) {
+        let mut affected = self
+            .passing
+            .remove(&removed)
+            .unwrap_or_default()
+            .into_iter();
+
+        let Some(replacement) = affected.next() else {
+            return;
+        };
+
+        let (_, endpoints) = self.remove_cycle(replacement);
+
+        self.spanning.add_edge(replacement, endpoints);
+
+        // other paths through the removed edge now go through the replacement
+        for edge in affected {
+            let (_, endpoints) = self.remove_cycle(edge);
+
+            self.insert_cycle(edge, endpoints);
+        }
+    }
+
+    fn insert_cycle(&mut self, edge: EdgeIndex, endpoints: [NodeIndex; 2]) {
+        let mut cycle = self.spanning.rooted().find_path(endpoints);
+
+        for &(tree_edge, _) in &cycle {
+            self.passing.entry(tree_edge).or_default().insert(edge);
+        }
+
+        cycle.push((edge, endpoints));
+
+        self.cycles.insert(edge, cycle);
+    }
+
+    /// Returns the edge closing the cycle with its endpoints.
+    fn remove_cycle(&mut self, edge: EdgeIndex) -> (EdgeIndex, [NodeIndex; 2]) {
+        let mut cycle = self.cycles.remove(&edge).unwrap();
+        let closing = cycle.pop().unwrap();
+
+        for (tree_edge, _) in cycle {
+            if let Some(passing) = self.passing.get_mut(&tree_edge) {
+                passing.remove(&edge);
+            }
+        }
+
+        closing
+    }
 }
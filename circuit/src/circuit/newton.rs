@@ -0,0 +1,20 @@
+use nalgebra::RealField;
+
+/// Limits of the Newton–Raphson iteration solving circuits with nonlinear elements.
+///
+/// The iteration stops when no voltage across an element changes by more than
+/// `tolerance * (1 + |voltage|)`, by default it's the square root of the machine epsilon.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NewtonOptions<T = f32> {
+    pub max_iterations: usize,
+    pub tolerance: T,
+}
+
+impl<T: RealField + Copy> Default for NewtonOptions<T> {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            tolerance: T::default_epsilon().sqrt(),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use super::ElementId;
+
+/// A quantity measured in the circuit.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Probe<N> {
+    /// Potential of the node relative to the ground.
+    Voltage(N),
+    /// Current of the element as in [`ElementState`](super::ElementState).
+    Current(ElementId),
+    /// Power consumed by the element as in [`ElementState`](super::ElementState).
+    Power(ElementId),
+}
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display};
+use std::io;
+
+use super::Probe;
+
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// Keeps the last `capacity` samples of its probes, a sample is taken after each
+/// [`Circuit::update`](crate::Circuit::update) of the circuit owning the recorder.
+#[derive(Clone, Debug)]
+pub struct Recorder<N, T = f32> {
+    probes: Vec<Probe<N>>,
+    samples: VecDeque<Sample<T>>,
+    capacity: usize,
+}
+
+/// Readings of the probes of a [`Recorder`] in their order at the `time` of an update,
+/// `None` if the quantity can't be measured, e.g. the node isn't connected to the ground.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sample<T = f32> {
+    pub time: T,
+    pub readings: Vec<Option<T>>,
+}
+
+impl<N: Copy + Eq, T: Copy> Recorder<N, T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            probes: vec![],
+            samples: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn probes(&self) -> &[Probe<N>] {
+        &self.probes
+    }
+
+    /// The samples are cleared so all of them have a reading of each probe.
+    pub fn add_probe(&mut self, probe: Probe<N>) {
+        if !self.probes.contains(&probe) {
+            self.probes.push(probe);
+            self.samples.clear();
+        }
+    }
+
+    pub fn remove_probe(&mut self, probe: Probe<N>) {
+        if let Some(i) = self.probes.iter().position(|&other| other == probe) {
+            self.probes.remove(i);
+
+            for sample in &mut self.samples {
+                sample.readings.remove(i);
+            }
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The oldest samples above the new capacity are dropped.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        let excess = self.samples.len().saturating_sub(capacity);
+        self.samples.drain(..excess);
+    }
+
+    /// Samples from the oldest to the newest.
+    pub fn samples(&self) -> impl ExactSizeIterator<Item = &Sample<T>> + '_ {
+        self.samples.iter()
+    }
+
+    /// Times and readings of a single probe, the samples it couldn't measure are skipped.
+    pub fn channel(&self, probe: Probe<N>) -> impl Iterator<Item = (T, T)> + '_ {
+        let i = self.probes.iter().position(|&other| other == probe);
+
+        self.samples
+            .iter()
+            .filter_map(move |sample| Some((sample.time, sample.readings[i?]?)))
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub(crate) fn push(&mut self, time: T, readings: Vec<Option<T>>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(Sample { time, readings });
+    }
+
+    /// Writes the samples as CSV with a header like `time,V(node),I(id),P(id)`,
+    /// readings which couldn't be measured are left empty. Header fields are quoted
+    /// as in RFC 4180 if the node names need it.
+    pub fn write_csv(&self, mut writer: impl io::Write) -> io::Result<()>
+    where
+        N: Debug,
+        T: Display,
+    {
+        write!(writer, "time")?;
+
+        for probe in &self.probes {
+            let name = match probe {
+                Probe::Voltage(node) => format!("V({node:?})"),
+                Probe::Current(id) => format!("I({})", id.0),
+                Probe::Power(id) => format!("P({})", id.0),
+            };
+
+            write!(writer, ",{}", csv_field(&name))?;
+        }
+
+        writeln!(writer)?;
+
+        for sample in &self.samples {
+            write!(writer, "{}", sample.time)?;
+
+            for reading in &sample.readings {
+                match reading {
+                    Some(reading) => write!(writer, ",{reading}")?,
+                    None => write!(writer, ",")?,
+                }
+            }
+
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<N: Copy + Eq, T: Copy> Default for Recorder<N, T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// Quotes the field if it contains a separator, a quote or a line break,
+/// quotes inside it are doubled.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
@@ -0,0 +1,153 @@
+mod loops;
+mod mna;
+mod sparse;
+mod stamp;
+
+use nalgebra::{DVector, RealField};
+
+use super::disjoint_set::DisjointSet;
+use super::graph::CircuitGraph;
+use super::{ElementId, SolveError};
+
+pub use loops::LoopSolver;
+pub use mna::MnaSolver;
+pub(crate) use mna::MnaSystem;
+pub(crate) use sparse::CsrMatrix;
+pub use stamp::{Stamp, Unknown};
+
+/// A method of finding currents and potentials of a circuit.
+pub trait Solver<T = f32> {
+    fn solve(&mut self, network: &Network<T>) -> Result<Solution<T>, SolveError>;
+}
+
+/// Snapshot of a circuit passed to a [`Solver`].
+pub struct Network<'circuit, T = f32> {
+    pub(crate) nodes_count: usize,
+    pub(crate) branches: Vec<Branch<T>>,
+    pub(crate) stamps: Vec<Stamp<T>>,
+    pub(crate) grounds: Vec<usize>,
+    pub(crate) graph: &'circuit CircuitGraph<T>,
+}
+
+impl<'circuit, T: RealField + Copy> Network<'circuit, T> {
+    pub fn nodes_count(&self) -> usize {
+        self.nodes_count
+    }
+
+    pub fn branches(&self) -> &[Branch<T>] {
+        &self.branches
+    }
+
+    /// Multi-terminal elements, see [`Circuit::add_multi`](crate::Circuit::add_multi).
+    pub fn stamps(&self) -> &[Stamp<T>] {
+        &self.stamps
+    }
+
+    /// The reference node of its part, the first of the [`Network::grounds`].
+    pub fn ground(&self) -> Option<usize> {
+        self.grounds.first().copied()
+    }
+
+    /// Nodes joined into one net at zero potential, see [`Circuit::set_ground`](crate::Circuit::set_ground).
+    pub fn grounds(&self) -> &[usize] {
+        &self.grounds
+    }
+
+    /// Currents forced into every part of the circuit joined by other branches
+    /// must sum up to zero.
+    pub fn check_forced_currents(&self) -> Result<(), SolveError> {
+        let mut parts = self.parts();
+
+        let mut sums = vec![T::zero(); self.nodes_count];
+        let mut scales = vec![T::one(); self.nodes_count];
+        let mut sources = vec![None; self.nodes_count];
+
+        for branch in &self.branches {
+            let Some(amperage) = branch.amperage else {
+                continue;
+            };
+
+            let [a, b] = branch.endpoints.map(|node| parts.find(node));
+
+            sums[a] -= amperage;
+            sums[b] += amperage;
+
+            for part in [a, b] {
+                scales[part] = scales[part].max(amperage.abs());
+                sources[part].get_or_insert(branch.id);
+            }
+        }
+
+        let tolerance = T::default_epsilon().sqrt();
+
+        for (part, sum) in sums.into_iter().enumerate() {
+            if sum.abs() > tolerance * scales[part] {
+                return Err(SolveError::OpenSource(sources[part].unwrap()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Elements passing currents to the ground need one, see [`Stamp::set_grounded`].
+    pub fn check_grounded(&self) -> Result<(), SolveError> {
+        if self.grounds.is_empty() && self.stamps.iter().any(Stamp::is_grounded) {
+            return Err(SolveError::Ungrounded);
+        }
+
+        Ok(())
+    }
+
+    /// Nodes joined by anything but current sources or by the ground, potentials of
+    /// different parts aren't related.
+    pub(crate) fn parts(&self) -> DisjointSet {
+        let mut parts = DisjointSet::new(self.nodes_count);
+
+        for grounds in self.grounds.windows(2) {
+            parts.union([grounds[0], grounds[1]]);
+        }
+
+        for branch in &self.branches {
+            if branch.amperage.is_none() {
+                parts.union(branch.endpoints);
+            }
+        }
+
+        for stamp in &self.stamps {
+            for nodes in stamp.connections() {
+                parts.union(nodes);
+            }
+        }
+
+        if let Some(&ground) = self.grounds.first() {
+            for node in self.stamps.iter().filter_map(Stamp::grounded_node) {
+                parts.union([ground, node]);
+            }
+        }
+
+        parts
+    }
+}
+
+/// A two-terminal element, the current flows from `endpoints[0]` to `endpoints[1]`,
+/// so the voltage drop across it is `resistance * current - emf`.
+/// If `amperage` is set, the current is forced and the drop is set by the rest of the circuit.
+#[derive(Clone, Copy)]
+pub struct Branch<T = f32> {
+    pub id: ElementId,
+    pub endpoints: [usize; 2],
+    pub emf: T,
+    pub resistance: T,
+    pub amperage: Option<T>,
+}
+
+pub struct Solution<T = f32> {
+    /// Currents of branches in the order of [`Network::branches`].
+    pub currents: DVector<T>,
+    /// Potentials of nodes, the ground or one node of every connected part of the circuit is at zero.
+    pub potentials: DVector<T>,
+    /// Currents flowing into the terminals of every stamp in the order of [`Network::stamps`].
+    pub terminal_currents: Vec<Vec<T>>,
+    /// Size of the solved linear system.
+    pub unknowns: usize,
+}
@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+
+use nalgebra::{DVector, RealField};
+
+use super::{Network, Solution, Solver};
+use crate::circuit::graph::CircuitGraph;
+use crate::circuit::SolveError;
+
+/// Loop current method: solves `L R Lᵀ i = L E` over the fundamental loops of the circuit.
+///
+/// Drops across current sources are extra unknowns, each of them adds
+/// the constraint that the loop currents through the source sum up to its amperage.
+/// Multi-terminal elements aren't supported, the grounds are merged into one node.
+#[derive(Default)]
+pub struct LoopSolver;
+
+impl<T: RealField + Copy> Solver<T> for LoopSolver {
+    fn solve(&mut self, network: &Network<T>) -> Result<Solution<T>, SolveError> {
+        if let Some(stamp) = network.stamps().first() {
+            return Err(SolveError::Unsupported(stamp.id()));
+        }
+
+        network.check_forced_currents()?;
+
+        // the loops of the circuit are cached without the grounds joined
+        let joined_graph;
+
+        let (loops, loops_transposed) = match network.grounds() {
+            [_, _, ..] => {
+                joined_graph = joined(network);
+                joined_graph.loops()
+            }
+            _ => network.graph.loops(),
+        };
+
+        let branches = network.branches();
+
+        let emf = branches.iter().map(|branch| match branch.amperage {
+            Some(_) => T::zero(),
+            None => branch.emf,
+        });
+        let emf = DVector::from_iterator(branches.len(), emf);
+
+        // `L R` without building the diagonal matrix `R`
+        let mut weighted_loops = loops.clone();
+
+        for (mut column, branch) in Iterator::zip(weighted_loops.column_iter_mut(), branches) {
+            match branch.amperage {
+                Some(_) => column.fill(T::zero()),
+                None => column *= branch.resistance,
+            }
+        }
+
+        let lhs = weighted_loops * loops_transposed;
+        let rhs = loops * emf;
+
+        // sources out of any loop are checked to have zero amperage
+        let sources: Vec<_> = (0..branches.len())
+            .filter(|&idx| branches[idx].amperage.is_some())
+            .filter(|&idx| {
+                !loops
+                    .column(idx)
+                    .iter()
+                    .all(|direction| direction.is_zero())
+            })
+            .collect();
+
+        for (i, row) in loops.row_iter().enumerate() {
+            if !lhs[(i, i)].is_zero() || sources.iter().any(|&idx| !row[idx].is_zero()) {
+                continue;
+            }
+
+            let cycle = row
+                .iter()
+                .enumerate()
+                .filter(|(_, direction)| !direction.is_zero());
+            let cycle = cycle.map(|(j, _)| branches[j]);
+
+            if !rhs[i].is_zero() {
+                let source = cycle.clone().find(|branch| !branch.emf.is_zero()).unwrap();
+
+                return Err(SolveError::ShortedSource(source.id));
+            }
+
+            return Err(SolveError::Singular {
+                cycle: cycle.map(|branch| branch.id).collect(),
+            });
+        }
+
+        let loops_count = loops.nrows();
+        let size = loops_count + sources.len();
+
+        let mut system = lhs.resize(size, size, T::zero());
+        let mut rhs = rhs.resize_vertically(size, T::zero());
+
+        for (k, &idx) in sources.iter().enumerate() {
+            let column = loops.column(idx);
+
+            system
+                .view_mut((0, loops_count + k), (loops_count, 1))
+                .copy_from(&column);
+            system
+                .view_mut((loops_count + k, 0), (1, loops_count))
+                .tr_copy_from(&column);
+
+            rhs[loops_count + k] = branches[idx].amperage.unwrap();
+        }
+
+        let unknowns = system
+            .qr()
+            .solve(&rhs)
+            .ok_or(SolveError::Singular { cycle: vec![] })?;
+
+        let mut currents = loops_transposed * unknowns.rows(0, loops_count);
+        let mut drops = DVector::zeros(branches.len());
+
+        for (idx, branch) in branches.iter().enumerate() {
+            match branch.amperage {
+                Some(amperage) => currents[idx] = amperage,
+                None => drops[idx] = branch.resistance * currents[idx] - branch.emf,
+            }
+        }
+
+        for (k, &idx) in sources.iter().enumerate() {
+            drops[idx] = unknowns[loops_count + k];
+        }
+
+        let potentials = potentials(network, &drops);
+
+        Ok(Solution {
+            currents,
+            potentials,
+            terminal_currents: vec![],
+            unknowns: size,
+        })
+    }
+}
+
+fn potentials<T: RealField + Copy>(network: &Network<T>, drops: &DVector<T>) -> DVector<T> {
+    let mut adjacent = vec![vec![]; network.nodes_count()];
+
+    let endpoints = |idx: usize| {
+        network.branches()[idx]
+            .endpoints
+            .map(|node| joined_node(network, node))
+    };
+
+    for idx in 0..network.branches().len() {
+        let [a, b] = endpoints(idx);
+
+        adjacent[a].push(idx);
+        adjacent[b].push(idx);
+    }
+
+    let mut potentials = DVector::zeros(network.nodes_count());
+    let mut visited = vec![false; network.nodes_count()];
+
+    for root in Iterator::chain(network.ground().into_iter(), 0..network.nodes_count()) {
+        if visited[root] {
+            continue;
+        }
+
+        visited[root] = true;
+
+        let mut queue = VecDeque::from_iter([root]);
+
+        while let Some(node) = queue.pop_front() {
+            for &idx in &adjacent[node] {
+                let [a, b] = endpoints(idx);
+                let drop = drops[idx];
+
+                let (other, potential) = if a == node {
+                    (b, potentials[node] - drop)
+                } else {
+                    (a, potentials[node] + drop)
+                };
+
+                if !visited[other] {
+                    visited[other] = true;
+                    potentials[other] = potential;
+
+                    queue.push_back(other);
+                }
+            }
+        }
+    }
+
+    potentials
+}
+
+/// The graph of the network with the grounds merged into the first one.
+fn joined<T: RealField + Copy>(network: &Network<T>) -> CircuitGraph<T> {
+    let mut graph = CircuitGraph::default();
+
+    let nodes: Vec<_> = (0..network.nodes_count())
+        .map(|_| graph.next_node())
+        .collect();
+
+    for branch in network.branches() {
+        graph.add_edge(
+            branch
+                .endpoints
+                .map(|node| nodes[joined_node(network, node)]),
+        );
+    }
+
+    graph
+}
+
+fn joined_node<T: RealField + Copy>(network: &Network<T>, node: usize) -> usize {
+    match network.grounds() {
+        [first, others @ ..] if others.contains(&node) => *first,
+        _ => node,
+    }
+}
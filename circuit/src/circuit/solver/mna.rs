@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+
+use nalgebra::{ComplexField, DVector, RealField};
+
+use super::sparse::CsrMatrix;
+use super::{Branch, Network, Solution, Solver, Stamp, Unknown};
+use crate::circuit::disjoint_set::DisjointSet;
+use crate::circuit::{ElementId, SolveError};
+
+/// Entries of a matrix as `(row, column, value)`, repeated positions are summed up.
+type Entries<S> = Vec<(usize, usize, S)>;
+
+/// Modified nodal analysis: the unknowns are potentials of nodes and currents of
+/// zero-resistance branches, so ideal sources and wires don't make the system singular.
+/// Multi-terminal elements stamp their entries directly and may add more currents.
+#[derive(Default)]
+pub struct MnaSolver;
+
+impl<T: RealField + Copy> Solver<T> for MnaSolver {
+    fn solve(&mut self, network: &Network<T>) -> Result<Solution<T>, SolveError> {
+        network.check_forced_currents()?;
+        network.check_grounded()?;
+
+        let system = MnaSystem::new(
+            network.nodes_count(),
+            network.branches(),
+            network.stamps(),
+            network.grounds(),
+            true,
+        );
+
+        let (lhs, rhs) = system.assemble()?;
+
+        let solution = CsrMatrix::from_triplets(system.size(), lhs)
+            .lu()
+            .ok_or(SolveError::Singular { cycle: vec![] })?
+            .solve(&rhs);
+
+        let potentials = system.potentials(&solution);
+
+        Ok(Solution {
+            currents: system.currents(&solution, &potentials)?,
+            potentials,
+            terminal_currents: system.terminal_currents(&solution),
+            unknowns: system.size(),
+        })
+    }
+}
+
+/// Unknowns and equations of the modified nodal analysis in real numbers for [`MnaSolver`]
+/// or in complex ones for the AC analysis, where the resistance of a branch is its impedance.
+pub(crate) struct MnaSystem<'network, T, S> {
+    branches: &'network [Branch<S>],
+    stamps: &'network [Stamp<T>],
+    /// The right-hand sides of the stamps are ignored if they don't drive the circuit.
+    stamp_sources: bool,
+
+    parts: DisjointSet,
+    node_unknowns: Vec<Option<usize>>,
+    /// Zero-resistance branches closing a loop of such branches.
+    closing: Vec<bool>,
+    branch_unknowns: Vec<Option<usize>>,
+    stamp_offsets: Vec<usize>,
+    sensed: HashMap<ElementId, usize>,
+    sensed_order: Vec<ElementId>,
+    size: usize,
+}
+
+impl<'network, T, S> MnaSystem<'network, T, S>
+where
+    T: RealField + Copy,
+    S: ComplexField<RealField = T> + Copy,
+{
+    pub fn new(
+        nodes_count: usize,
+        branches: &'network [Branch<S>],
+        stamps: &'network [Stamp<T>],
+        grounds: &[usize],
+        stamp_sources: bool,
+    ) -> Self {
+        // a part joined to the rest only by current sources has its own reference node
+        let mut parts = DisjointSet::new(nodes_count);
+        let mut shorts = DisjointSet::new(nodes_count);
+
+        for grounds in grounds.windows(2) {
+            parts.union([grounds[0], grounds[1]]);
+            shorts.union([grounds[0], grounds[1]]);
+        }
+
+        for branch in branches {
+            if branch.amperage.is_none() {
+                parts.union(branch.endpoints);
+            }
+        }
+
+        for stamp in stamps {
+            for nodes in stamp.connections() {
+                parts.union(nodes);
+            }
+        }
+
+        // the current of a grounded terminal returns through the ground
+        if let Some(&ground) = grounds.first() {
+            for node in stamps.iter().filter_map(Stamp::grounded_node) {
+                parts.union([ground, node]);
+            }
+        }
+
+        let mut size = 0;
+
+        let mut next_unknown = || {
+            size += 1;
+            size - 1
+        };
+
+        let ground = grounds.first().map(|&ground| (parts.find(ground), ground));
+
+        // the ground or the root of every connected part is its reference node,
+        // all the grounds are at zero like a single node
+        let node_unknowns: Vec<_> = (0..nodes_count)
+            .map(|node| {
+                let reference = match (parts.find(node), ground) {
+                    (part, Some((ground_part, ground))) if part == ground_part => ground,
+                    (part, _) => part,
+                };
+
+                (node != reference && !grounds.contains(&node)).then(&mut next_unknown)
+            })
+            .collect();
+
+        // a zero-resistance branch closing a loop of such branches gets no unknown,
+        // otherwise the current around this loop would be undetermined;
+        // wires are joined first, so a loop with a source is always closed by a source
+        let mut closing = vec![false; branches.len()];
+
+        for emf_pass in [false, true] {
+            for (idx, branch) in branches.iter().enumerate() {
+                if is_voltage_defined(branch) && branch.emf.is_zero() != emf_pass {
+                    closing[idx] = !shorts.union(branch.endpoints);
+                }
+            }
+        }
+
+        let branch_unknowns: Vec<_> = Iterator::zip(branches.iter(), &closing)
+            .map(|(branch, &closing)| {
+                (is_voltage_defined(branch) && !closing).then(&mut next_unknown)
+            })
+            .collect();
+
+        let stamp_offsets: Vec<_> = stamps
+            .iter()
+            .map(|stamp| {
+                let offset = size;
+                size += stamp.currents();
+                offset
+            })
+            .collect();
+
+        // currents controlling other elements get their own unknowns
+        let mut sensed = HashMap::new();
+        let mut sensed_order = vec![];
+
+        for stamp in stamps {
+            for &(_, column, _) in stamp.entries() {
+                if let Unknown::ElementCurrent(id) = column {
+                    sensed.entry(id).or_insert_with(|| {
+                        sensed_order.push(id);
+                        size += 1;
+                        size - 1
+                    });
+                }
+            }
+        }
+
+        Self {
+            branches,
+            stamps,
+            stamp_sources,
+            parts,
+            node_unknowns,
+            closing,
+            branch_unknowns,
+            stamp_offsets,
+            sensed,
+            sensed_order,
+            size,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Nodes with the same part have related potentials.
+    pub fn part(&mut self, node: usize) -> usize {
+        self.parts.find(node)
+    }
+
+    /// The matrix and the right-hand side of the system.
+    pub fn assemble(&self) -> Result<(Entries<S>, DVector<S>), SolveError> {
+        let index = |stamp, offset, unknown| self.index(stamp, offset, unknown);
+
+        let mut lhs = vec![];
+        let mut rhs = DVector::from_element(self.size, S::zero());
+
+        for (branch, &branch_unknown) in Iterator::zip(self.branches.iter(), &self.branch_unknowns)
+        {
+            let [a, b] = branch.endpoints.map(|node| self.node_unknowns[node]);
+
+            if let Some(amperage) = branch.amperage {
+                if let Some(a) = a {
+                    rhs[a] -= amperage;
+                }
+
+                if let Some(b) = b {
+                    rhs[b] += amperage;
+                }
+            } else if let Some(k) = branch_unknown {
+                if let Some(a) = a {
+                    lhs.push((a, k, S::one()));
+                    lhs.push((k, a, S::one()));
+                }
+
+                if let Some(b) = b {
+                    lhs.push((b, k, -S::one()));
+                    lhs.push((k, b, -S::one()));
+                }
+
+                rhs[k] = -branch.emf;
+            } else if !branch.resistance.is_zero() {
+                let conductance = S::one() / branch.resistance;
+
+                for (this, other, sign) in [(a, b, S::one()), (b, a, -S::one())] {
+                    let Some(this) = this else {
+                        continue;
+                    };
+
+                    lhs.push((this, this, conductance));
+
+                    if let Some(other) = other {
+                        lhs.push((this, other, -conductance));
+                    }
+
+                    rhs[this] -= sign * conductance * branch.emf;
+                }
+            }
+        }
+
+        for (stamp, &offset) in Iterator::zip(self.stamps.iter(), &self.stamp_offsets) {
+            let row_index = |row| match row {
+                Unknown::ElementCurrent(_) => None,
+                _ => index(stamp, offset, row),
+            };
+
+            for &(row, column, value) in stamp.entries() {
+                if let (Some(row), Some(column)) = (row_index(row), index(stamp, offset, column)) {
+                    lhs.push((row, column, S::from_real(value)));
+                }
+            }
+
+            for &(row, value) in stamp.rhs().iter().filter(|_| self.stamp_sources) {
+                if let Some(row) = row_index(row) {
+                    rhs[row] += S::from_real(value);
+                }
+            }
+        }
+
+        // `sensed - current = 0` with the current expressed by the unknowns of its element
+        for &id in &self.sensed_order {
+            let sensed = self.sensed[&id];
+
+            lhs.push((sensed, sensed, S::one()));
+
+            if let Some(idx) = self.branches.iter().position(|branch| branch.id == id) {
+                let branch = &self.branches[idx];
+                let [a, b] = branch.endpoints.map(|node| self.node_unknowns[node]);
+
+                match (branch.amperage, self.branch_unknowns[idx]) {
+                    (Some(amperage), _) => rhs[sensed] += amperage,
+                    (None, Some(k)) => lhs.push((sensed, k, -S::one())),
+                    (None, None) if branch.resistance.is_zero() => {}
+                    (None, None) => {
+                        let conductance = S::one() / branch.resistance;
+
+                        if let Some(a) = a {
+                            lhs.push((sensed, a, -conductance));
+                        }
+
+                        if let Some(b) = b {
+                            lhs.push((sensed, b, conductance));
+                        }
+
+                        rhs[sensed] += conductance * branch.emf;
+                    }
+                }
+            } else if let Some(idx) = self.stamps.iter().position(|stamp| stamp.id() == id) {
+                let (stamp, offset) = (&self.stamps[idx], self.stamp_offsets[idx]);
+
+                for &(row, column, value) in stamp.entries() {
+                    if row != Unknown::Potential(0) {
+                        continue;
+                    }
+
+                    if let Some(column) = index(stamp, offset, column) {
+                        lhs.push((sensed, column, -S::from_real(value)));
+                    }
+                }
+
+                for &(row, value) in stamp.rhs().iter().filter(|_| self.stamp_sources) {
+                    if row == Unknown::Potential(0) {
+                        rhs[sensed] -= S::from_real(value);
+                    }
+                }
+            } else {
+                return Err(SolveError::UnknownElement(id));
+            }
+        }
+
+        Ok((lhs, rhs))
+    }
+
+    /// Potentials of nodes from the solution of the system.
+    pub fn potentials(&self, solution: &DVector<S>) -> DVector<S> {
+        DVector::from_iterator(
+            self.node_unknowns.len(),
+            self.node_unknowns
+                .iter()
+                .map(|unknown| unknown.map_or(S::zero(), |idx| solution[idx])),
+        )
+    }
+
+    /// Currents of branches, a source closing a loop of zero-resistance branches
+    /// is shorted unless the loop agrees with its emf.
+    pub fn currents(
+        &self,
+        solution: &DVector<S>,
+        potentials: &DVector<S>,
+    ) -> Result<DVector<S>, SolveError> {
+        let branches = Iterator::zip(self.branches.iter(), &self.branch_unknowns);
+
+        let currents =
+            Iterator::zip(branches, &self.closing).map(|((branch, &unknown), &closing)| {
+                let [a, b] = branch.endpoints.map(|node| potentials[node]);
+
+                let scale = [a, b, branch.emf]
+                    .map(|value| value.modulus())
+                    .into_iter()
+                    .fold(T::one(), T::max);
+                let tolerance = T::default_epsilon().sqrt() * scale;
+
+                if closing && (a - b + branch.emf).modulus() > tolerance {
+                    return Err(SolveError::ShortedSource(branch.id));
+                }
+
+                Ok(match (branch.amperage, unknown) {
+                    (Some(amperage), _) => amperage,
+                    (None, Some(k)) => solution[k],
+                    (None, None) if branch.resistance.is_zero() => S::zero(),
+                    (None, None) => (a - b + branch.emf) / branch.resistance,
+                })
+            });
+
+        Ok(DVector::from_vec(currents.collect::<Result<_, _>>()?))
+    }
+
+    /// Currents flowing into the terminals of every stamp.
+    pub fn terminal_currents(&self, solution: &DVector<S>) -> Vec<Vec<S>> {
+        let value = |stamp, offset, unknown| {
+            self.index(stamp, offset, unknown)
+                .map_or(S::zero(), |idx| solution[idx])
+        };
+
+        Iterator::zip(self.stamps.iter(), &self.stamp_offsets)
+            .map(|(stamp, &offset)| {
+                let mut currents = vec![S::zero(); stamp.terminals()];
+
+                for &(row, column, entry) in stamp.entries() {
+                    if let Unknown::Potential(terminal) = row {
+                        currents[terminal] += S::from_real(entry) * value(stamp, offset, column);
+                    }
+                }
+
+                for &(row, rhs) in stamp.rhs().iter().filter(|_| self.stamp_sources) {
+                    if let Unknown::Potential(terminal) = row {
+                        currents[terminal] -= S::from_real(rhs);
+                    }
+                }
+
+                currents
+            })
+            .collect()
+    }
+
+    /// Index of the unknown of a stamp with its currents starting at `offset`,
+    /// `None` for the reference nodes.
+    fn index(&self, stamp: &Stamp<T>, offset: usize, unknown: Unknown) -> Option<usize> {
+        match unknown {
+            Unknown::Potential(terminal) => self.node_unknowns[stamp.nodes()[terminal]],
+            Unknown::Current(k) => Some(offset + k),
+            Unknown::ElementCurrent(id) => Some(self.sensed[&id]),
+        }
+    }
+}
+
+fn is_voltage_defined<S: ComplexField + Copy>(branch: &Branch<S>) -> bool {
+    branch.amperage.is_none() && branch.resistance.is_zero()
+}
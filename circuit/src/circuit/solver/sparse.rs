@@ -0,0 +1,389 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::mem;
+
+use nalgebra::{ComplexField, DVector, RealField};
+
+/// A pivot is accepted if it isn't less than this part of the biggest value in its column.
+const PIVOT_THRESHOLD: f64 = 0.1;
+
+/// A square matrix in the compressed sparse row format.
+pub struct CsrMatrix<T> {
+    offsets: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: ComplexField + Copy> CsrMatrix<T> {
+    /// Values of repeated positions are summed up.
+    pub fn from_triplets(size: usize, mut triplets: Vec<(usize, usize, T)>) -> Self {
+        triplets.sort_unstable_by_key(|&(row, column, _)| (row, column));
+
+        let mut offsets = vec![0; size + 1];
+        let mut columns = Vec::with_capacity(triplets.len());
+        let mut values = Vec::with_capacity(triplets.len());
+
+        let mut last = None;
+
+        for (row, column, value) in triplets {
+            if last == Some((row, column)) {
+                *values.last_mut().unwrap() += value;
+            } else {
+                last = Some((row, column));
+
+                offsets[row + 1] += 1;
+                columns.push(column);
+                values.push(value);
+            }
+        }
+
+        for row in 0..size {
+            offsets[row + 1] += offsets[row];
+        }
+
+        Self {
+            offsets,
+            columns,
+            values,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn row(&self, row: usize) -> impl Iterator<Item = (usize, T)> + '_ {
+        let range = self.offsets[row]..self.offsets[row + 1];
+
+        Iterator::zip(
+            self.columns[range.clone()].iter().copied(),
+            self.values[range].iter().copied(),
+        )
+    }
+
+    /// Gaussian elimination choosing the column with the fewest entries as the next pivot
+    /// column to keep the fill-in small, `None` if the matrix is singular.
+    pub fn lu(&self) -> Option<SparseLu<T>> {
+        let size = self.size();
+
+        let mut rows: Vec<Vec<_>> = (0..size).map(|row| self.row(row).collect()).collect();
+
+        // rows of every column, may contain already pivoted rows
+        let mut column_rows = vec![vec![]; size];
+
+        for (idx, row) in rows.iter().enumerate() {
+            for &(column, _) in row {
+                column_rows[column].push(idx);
+            }
+        }
+
+        let mut counts: Vec<_> = column_rows.iter().map(Vec::len).collect();
+
+        let mut pivoted = vec![false; size];
+        let mut eliminated = vec![false; size];
+
+        // may contain outdated counts, they are skipped when popped
+        let mut queue: BinaryHeap<_> = counts
+            .iter()
+            .enumerate()
+            .map(|(column, &count)| Reverse((count, column)))
+            .collect();
+
+        let mut lower = vec![];
+        let mut upper = Vec::with_capacity(size);
+
+        let threshold = nalgebra::convert::<f64, T::RealField>(PIVOT_THRESHOLD);
+
+        let mut merged = vec![];
+        let mut new_columns = vec![];
+
+        while let Some(Reverse((count, column))) = queue.pop() {
+            if eliminated[column] || count != counts[column] {
+                continue;
+            }
+
+            eliminated[column] = true;
+
+            let mut candidates = mem::take(&mut column_rows[column]);
+            candidates.retain(|&row| !pivoted[row]);
+
+            let max = candidates
+                .iter()
+                .map(|&row| entry(&rows[row], column).modulus())
+                .fold(nalgebra::zero(), T::RealField::max);
+
+            if max == nalgebra::zero() {
+                return None;
+            }
+
+            let min = threshold.clone() * max;
+
+            let pivot_row = candidates
+                .iter()
+                .copied()
+                .filter(|&row| entry(&rows[row], column).modulus() >= min)
+                .min_by_key(|&row| rows[row].len())
+                .unwrap();
+
+            pivoted[pivot_row] = true;
+
+            let mut pivot_entries = mem::take(&mut rows[pivot_row]);
+            let pivot_value = entry(&pivot_entries, column);
+
+            pivot_entries.retain(|&(other_column, _)| other_column != column);
+
+            for &(other_column, _) in &pivot_entries {
+                counts[other_column] -= 1;
+            }
+
+            for target in candidates.into_iter().filter(|&row| row != pivot_row) {
+                let factor = entry(&rows[target], column) / pivot_value;
+
+                lower.push((pivot_row, target, factor));
+
+                subtract_scaled(
+                    &rows[target],
+                    &pivot_entries,
+                    factor,
+                    column,
+                    &mut merged,
+                    &mut new_columns,
+                );
+
+                mem::swap(&mut rows[target], &mut merged);
+
+                for new_column in new_columns.drain(..) {
+                    column_rows[new_column].push(target);
+                    counts[new_column] += 1;
+                }
+            }
+
+            // only columns of the pivot row could change their counts
+            for &(other_column, _) in &pivot_entries {
+                queue.push(Reverse((counts[other_column], other_column)));
+            }
+
+            upper.push(Pivot {
+                row: pivot_row,
+                column,
+                value: pivot_value,
+                entries: pivot_entries,
+            });
+        }
+
+        Some(SparseLu { lower, upper })
+    }
+}
+
+pub struct SparseLu<T> {
+    /// Row operations `rhs[target] -= factor * rhs[source]` in the order of elimination.
+    lower: Vec<(usize, usize, T)>,
+    upper: Vec<Pivot<T>>,
+}
+
+struct Pivot<T> {
+    row: usize,
+    column: usize,
+    value: T,
+    entries: Vec<(usize, T)>,
+}
+
+impl<T: ComplexField + Copy> SparseLu<T> {
+    pub fn solve(&self, rhs: &DVector<T>) -> DVector<T> {
+        let mut rhs = rhs.clone();
+
+        for &(source, target, factor) in &self.lower {
+            let source = rhs[source];
+
+            rhs[target] -= factor * source;
+        }
+
+        let mut solution = DVector::zeros(rhs.len());
+
+        for pivot in self.upper.iter().rev() {
+            let known = pivot
+                .entries
+                .iter()
+                .fold(T::zero(), |known, &(column, value)| {
+                    known + value * solution[column]
+                });
+
+            solution[pivot.column] = (rhs[pivot.row] - known) / pivot.value;
+        }
+
+        solution
+    }
+}
+
+fn entry<T: ComplexField + Copy>(row: &[(usize, T)], column: usize) -> T {
+    row.binary_search_by_key(&column, |&(column, _)| column)
+        .map_or(T::zero(), |idx| row[idx].1)
+}
+
+/// Writes `target - factor * source` dropping the `skip` column into `result`,
+/// columns which were absent in `target` are added to `new_columns`.
+fn subtract_scaled<T: ComplexField + Copy>(
+    target: &[(usize, T)],
+    source: &[(usize, T)],
+    factor: T,
+    skip: usize,
+    result: &mut Vec<(usize, T)>,
+    new_columns: &mut Vec<usize>,
+) {
+    result.clear();
+
+    let mut target = target.iter().copied().peekable();
+    let mut source = source.iter().copied().peekable();
+
+    loop {
+        let next = match (target.peek(), source.peek()) {
+            (Some(&(a, _)), Some(&(b, _))) if a == b => {
+                let (column, value) = target.next().unwrap();
+                let (_, other_value) = source.next().unwrap();
+
+                (column, value - factor * other_value)
+            }
+            (Some(&(a, _)), Some(&(b, _))) if b < a => {
+                let (column, value) = source.next().unwrap();
+
+                new_columns.push(column);
+
+                (column, -factor * value)
+            }
+            (Some(_), _) => target.next().unwrap(),
+            (None, Some(_)) => {
+                let (column, value) = source.next().unwrap();
+
+                new_columns.push(column);
+
+                (column, -factor * value)
+            }
+            (None, None) => break,
+        };
+
+        if next.0 != skip {
+            result.push(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Complex, DMatrix, DVector};
+
+    use super::CsrMatrix;
+
+    /// A linear congruential generator giving values in `-1..1`.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> f64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+
+            (self.0 >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+        }
+
+        fn index(&mut self, size: usize) -> usize {
+            ((self.next() + 1.0) / 2.0 * size as f64) as usize % size
+        }
+    }
+
+    fn random_triplets(random: &mut Random, size: usize) -> Vec<(usize, usize, f64)> {
+        let mut triplets = vec![];
+
+        for row in 0..size {
+            for _ in 0..3 {
+                triplets.push((row, random.index(size), random.next()));
+            }
+
+            // a permuted diagonal which may also be tiny to exercise the pivoting
+            triplets.push((row, (row * 7 + 3) % size, random.next() * 0.01));
+        }
+
+        triplets
+    }
+
+    #[test]
+    fn matches_dense_lu() {
+        let mut random = Random(1);
+
+        for size in [1, 2, 5, 20, 100] {
+            for _ in 0..20 {
+                let triplets = random_triplets(&mut random, size);
+                let rhs = DVector::from_fn(size, |_, _| random.next());
+
+                let mut dense = DMatrix::zeros(size, size);
+
+                for &(row, column, value) in &triplets {
+                    dense[(row, column)] += value;
+                }
+
+                let expected = dense.clone().lu().solve(&rhs);
+                let sparse = CsrMatrix::from_triplets(size, triplets).lu();
+
+                let Some(expected) = expected else {
+                    continue;
+                };
+
+                // a nearly singular matrix may be rejected by either of them
+                let Some(sparse) = sparse else {
+                    assert!(dense.determinant().abs() < 1e-9);
+                    continue;
+                };
+
+                let actual = sparse.solve(&rhs);
+
+                assert!((&dense * &actual - &rhs).norm() < 1e-8 * (1.0 + dense.norm()));
+                assert!((actual - &expected).norm() < 1e-6 * (1.0 + expected.norm()));
+            }
+        }
+    }
+
+    #[test]
+    fn complex_values() {
+        let i = Complex::new(0.0, 1.0);
+        let one = Complex::new(1.0, 0.0);
+
+        let triplets = vec![(0, 0, i), (0, 1, one), (1, 0, one), (1, 1, i)];
+        let rhs = DVector::from_vec(vec![one, i]);
+
+        let solution = CsrMatrix::from_triplets(2, triplets)
+            .lu()
+            .unwrap()
+            .solve(&rhs);
+
+        // i * x + y = 1, x + i * y = i
+        assert!((solution[0] * i + solution[1] - one).norm() < 1e-12);
+        assert!((solution[0] + solution[1] * i - i).norm() < 1e-12);
+    }
+
+    #[test]
+    fn singular() {
+        let triplets = vec![
+            (0, 0, 1.0),
+            (0, 1, 2.0),
+            (1, 0, 2.0),
+            (1, 1, 4.0),
+            (2, 2, 1.0),
+        ];
+
+        assert!(CsrMatrix::from_triplets(3, triplets).lu().is_none());
+
+        // an empty column
+        let triplets = vec![(0, 0, 1.0), (1, 0, 1.0)];
+
+        assert!(CsrMatrix::from_triplets(2, triplets).lu().is_none());
+    }
+
+    #[test]
+    fn repeated_positions_are_summed() {
+        let triplets = vec![(0, 0, 1.0), (0, 0, 2.0), (1, 1, 1.0), (1, 0, -1.0)];
+        let matrix = CsrMatrix::from_triplets(2, triplets);
+
+        assert_eq!(matrix.row(0).collect::<Vec<_>>(), vec![(0, 3.0)]);
+        assert_eq!(matrix.row(1).collect::<Vec<_>>(), vec![(0, -1.0), (1, 1.0)]);
+    }
+}
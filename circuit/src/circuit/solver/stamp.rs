@@ -0,0 +1,209 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use nalgebra::RealField;
+
+use crate::circuit::ElementId;
+
+/// An unknown of the system local to a [`Stamp`]: the potential of a terminal or
+/// a current added with [`Stamp::add_current`]. As a row it's the sum of currents
+/// flowing into the element through the terminal or the equation of the current.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Unknown {
+    Potential(usize),
+    Current(usize),
+    /// The current of another element as in [`ElementState`](crate::circuit::ElementState),
+    /// it can only be a column.
+    ElementCurrent(ElementId),
+}
+
+/// Contribution of a multi-terminal element to the system of modified nodal analysis,
+/// the terminals are numbered in the order they were passed to
+/// [`Circuit::add_multi`](crate::Circuit::add_multi).
+pub struct Stamp<T = f32> {
+    id: ElementId,
+    nodes: Vec<usize>,
+    potentials: Vec<T>,
+    /// Set once the potentials are read, so the element is solved by iterations.
+    nonlinear: Cell<bool>,
+    /// The terminal the current returns to the ground from.
+    grounded: Option<usize>,
+
+    currents: usize,
+    entries: Vec<(Unknown, Unknown, T)>,
+    rhs: Vec<(Unknown, T)>,
+}
+
+impl<T: RealField + Copy> Stamp<T> {
+    pub(crate) fn new(id: ElementId, nodes: Vec<usize>, potentials: Vec<T>) -> Self {
+        Self {
+            id,
+            nodes,
+            potentials,
+            nonlinear: Cell::new(false),
+            grounded: None,
+            currents: 0,
+            entries: vec![],
+            rhs: vec![],
+        }
+    }
+
+    pub fn id(&self) -> ElementId {
+        self.id
+    }
+
+    /// Nodes of the terminals.
+    pub fn nodes(&self) -> &[usize] {
+        &self.nodes
+    }
+
+    pub fn terminals(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Potentials of the terminals at the previous Newton–Raphson iteration,
+    /// reading them makes the element nonlinear.
+    pub fn potentials(&self) -> &[T] {
+        self.nonlinear.set(true);
+
+        &self.potentials
+    }
+
+    /// `φ(a) - φ(b)` at the previous iteration, see [`Stamp::potentials`].
+    pub fn voltage(&self, [a, b]: [usize; 2]) -> T {
+        let potentials = self.potentials();
+
+        potentials[a] - potentials[b]
+    }
+
+    /// Doesn't make the element nonlinear unlike [`Stamp::voltage`].
+    pub(crate) fn previous_voltage(&self, [a, b]: [usize; 2]) -> T {
+        self.potentials[a] - self.potentials[b]
+    }
+
+    pub(crate) fn set_nonlinear(&self) {
+        self.nonlinear.set(true);
+    }
+
+    pub fn is_nonlinear(&self) -> bool {
+        self.nonlinear.get()
+    }
+
+    /// Marks the terminal as passing a current to the ground, e.g. the output of
+    /// an operational amplifier, so the circuit can't be solved without a ground.
+    pub fn set_grounded(&mut self, terminal: usize) {
+        self.grounded = Some(terminal);
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.grounded.is_some()
+    }
+
+    /// Node of the terminal set with [`Stamp::set_grounded`].
+    pub(crate) fn grounded_node(&self) -> Option<usize> {
+        self.grounded.map(|terminal| self.nodes[terminal])
+    }
+
+    /// Count of the added currents.
+    pub fn currents(&self) -> usize {
+        self.currents
+    }
+
+    pub fn entries(&self) -> &[(Unknown, Unknown, T)] {
+        &self.entries
+    }
+
+    pub fn rhs(&self) -> &[(Unknown, T)] {
+        &self.rhs
+    }
+
+    /// Adds an unknown current, its row is empty until an equation is stamped there.
+    pub fn add_current(&mut self) -> usize {
+        self.currents += 1;
+        self.currents - 1
+    }
+
+    /// Adds `value` to the matrix of the system at `row` and `column`.
+    pub fn entry(&mut self, row: Unknown, column: Unknown, value: T) {
+        self.entries.push((row, column, value));
+    }
+
+    /// Adds `value` to the right-hand side of the system at `row`.
+    pub fn add_rhs(&mut self, row: Unknown, value: T) {
+        self.rhs.push((row, value));
+    }
+
+    /// The current `transconductance * (φ(c) - φ(d))` flows from the terminal `a`
+    /// to the terminal `b` through the element.
+    pub fn transconductance(&mut self, [a, b]: [usize; 2], [c, d]: [usize; 2], value: T) {
+        use Unknown::Potential;
+
+        self.entry(Potential(a), Potential(c), value);
+        self.entry(Potential(a), Potential(d), -value);
+        self.entry(Potential(b), Potential(c), -value);
+        self.entry(Potential(b), Potential(d), value);
+    }
+
+    pub fn conductance(&mut self, terminals: [usize; 2], value: T) {
+        self.transconductance(terminals, terminals, value);
+    }
+
+    /// The constant `current` flows from the terminal `a` to the terminal `b`
+    /// through the element.
+    pub fn current(&mut self, [a, b]: [usize; 2], current: T) {
+        self.add_rhs(Unknown::Potential(a), -current);
+        self.add_rhs(Unknown::Potential(b), current);
+    }
+
+    /// Makes `φ(a) - φ(b)` equal to `voltage`, returns the added current
+    /// flowing from `a` to `b` through the element.
+    pub fn voltage_source(&mut self, [a, b]: [usize; 2], voltage: T) -> usize {
+        use Unknown::{Current, Potential};
+
+        let k = self.add_current();
+
+        self.entry(Potential(a), Current(k), T::one());
+        self.entry(Potential(b), Current(k), -T::one());
+        self.entry(Current(k), Potential(a), T::one());
+        self.entry(Current(k), Potential(b), -T::one());
+        self.add_rhs(Current(k), voltage);
+
+        k
+    }
+
+    /// The current `gain * control` flows from the terminal `a` to the terminal `b`
+    /// through the element, `control` is a column unknown.
+    pub fn controlled_current(&mut self, [a, b]: [usize; 2], control: Unknown, gain: T) {
+        self.entry(Unknown::Potential(a), control, gain);
+        self.entry(Unknown::Potential(b), control, -gain);
+    }
+
+    /// Makes `φ(a) - φ(b)` equal to `gain * control`, `control` is a column unknown.
+    /// Returns the added current flowing from `a` to `b` through the element.
+    pub fn controlled_voltage(&mut self, [a, b]: [usize; 2], control: Unknown, gain: T) -> usize {
+        let k = self.voltage_source([a, b], T::zero());
+
+        self.entry(Unknown::Current(k), control, -gain);
+
+        k
+    }
+
+    /// Pairs of nodes a current can flow between through the element:
+    /// terminals whose rows depend on the same unknown.
+    pub(crate) fn connections(&self) -> impl Iterator<Item = [usize; 2]> + '_ {
+        let mut first = HashMap::new();
+
+        self.entries
+            .iter()
+            .filter_map(move |&(row, column, _)| {
+                let Unknown::Potential(terminal) = row else {
+                    return None;
+                };
+
+                let first = *first.entry(column).or_insert(terminal);
+
+                (first != terminal).then_some([first, terminal])
+            })
+            .map(|terminals| terminals.map(|terminal| self.nodes[terminal]))
+    }
+}
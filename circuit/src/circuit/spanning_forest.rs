@@ -1,118 +1,175 @@
-use std::collections::VecDeque;
-use std::iter;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use super::bfs::bfs_nodes_as_undirected;
-use super::matrix::{MatrixGraph, NodeIndex};
+use super::adjacency::{AdjacencyGraph, EdgeIndex, NodeIndex};
 
 #[derive(Default)]
 pub struct SpanningForest {
-    forest: MatrixGraph,
-    roots: Vec<NodeIndex>,
+    forest: AdjacencyGraph,
+    edges: HashSet<EdgeIndex>,
+    rooted: RootedForest,
+
+    /// Label of the tree every node belongs to.
+    trees: Vec<usize>,
+    sizes: HashMap<usize, usize>,
+    labels_count: usize,
 }
 
 impl SpanningForest {
-    pub fn build(graph: &MatrixGraph) -> Self {
-        let mut forest = MatrixGraph::new(graph.nodes_count());
-
-        let mut visited = vec![false; graph.nodes_count()];
-
-        let roots: Vec<_> = iter::from_fn(|| {
-            let root = visited
-                .iter()
-                .enumerate()
-                .find(|(_, &visited)| !visited)
-                .map(|(idx, _)| NodeIndex(idx));
-
-            root.inspect(|&root| {
-                let mut queue = VecDeque::from_iter([root]);
-
-                while let Some(idx) = queue.pop_front() {
-                    visited[idx.0] = true;
-
-                    graph
-                        .neighbour_nodes(idx)
-                        .filter(|&other_idx| !visited[other_idx.0])
-                        .filter(|&other_idx| {
-                            graph
-                                .neighbour_nodes(other_idx)
-                                .all(|other_idx| other_idx == idx || !visited[other_idx.0])
-                        })
-                        .for_each(|other_idx| {
-                            forest.add_edge([idx, other_idx]);
-
-                            queue.push_back(other_idx);
-                        });
-                }
-            })
-        })
-        .collect();
+    pub fn next_node(&mut self) {
+        self.forest.next_node();
+        self.rooted.parents.push(None);
+        self.rooted.depths.push(0);
 
-        Self { forest, roots }
-    }
+        let label = self.new_label();
 
-    pub fn next_node(&mut self) {
-        let new_node = self.forest.next_node();
-        self.roots.push(new_node);
+        self.trees.push(label);
+        self.sizes.insert(label, 1);
     }
 
-    pub fn add_edge(&mut self, endpoints: [NodeIndex; 2]) -> bool {
-        let roots_and_indexes = endpoints.map(|endpoint| {
-            bfs_nodes_as_undirected(&self.forest, endpoint)
-                .find_map(|node| {
-                    self.roots
-                        .iter()
-                        .position(|&root| node == root)
-                        .map(|index| (index, node))
-                })
-                .unwrap()
-        });
-
-        let roots = roots_and_indexes.map(|(_, root)| root);
-        let indexes = roots_and_indexes.map(|(index, _)| index);
-
-        if roots[0] != roots[1] {
-            self.roots.remove(indexes[1]);
-            self.forest.add_edge(endpoints);
-
-            true
+    /// Adds the edge to the forest if it connects two trees.
+    pub fn add_edge(&mut self, edge: EdgeIndex, [a, b]: [NodeIndex; 2]) -> bool {
+        let labels = [self.trees[a.0], self.trees[b.0]];
+
+        if labels[0] == labels[1] {
+            return false;
+        }
+
+        // the smaller tree joins the bigger one hanging from the other endpoint
+        let (big, small, parent) = if self.sizes[&labels[0]] >= self.sizes[&labels[1]] {
+            (labels[0], b, a)
         } else {
-            false
+            (labels[1], a, b)
+        };
+
+        let small_nodes = self.hang(small, (edge, parent));
+        let small_label = self.trees[small.0];
+
+        for &node in &small_nodes {
+            self.trees[node.0] = big;
         }
+
+        self.sizes.remove(&small_label);
+        *self.sizes.get_mut(&big).unwrap() += small_nodes.len();
+
+        self.forest.add_edge(edge, [a, b]);
+        self.edges.insert(edge);
+
+        true
+    }
+
+    /// Removes the edge if it's in the forest, its tree splits in two.
+    pub fn remove_edge(&mut self, edge: EdgeIndex, [a, b]: [NodeIndex; 2]) -> bool {
+        if !self.edges.remove(&edge) {
+            return false;
+        }
+
+        self.forest.remove_edge(edge, [a, b]);
+
+        // the part below the edge becomes a tree hanging from its endpoint
+        for node in [a, b] {
+            if self.rooted.parents[node.0].map(|(parent_edge, _)| parent_edge) == Some(edge) {
+                self.rooted.parents[node.0] = None;
+            }
+        }
+
+        let split_nodes = self.tree_nodes(b);
+        let label = self.new_label();
+
+        for &node in &split_nodes {
+            self.trees[node.0] = label;
+        }
+
+        *self.sizes.get_mut(&self.trees[a.0]).unwrap() -= split_nodes.len();
+        self.sizes.insert(label, split_nodes.len());
+
+        true
     }
 
-    pub fn has_edge(&self, endpoints: [NodeIndex; 2]) -> bool {
-        self.forest.has_edge(endpoints)
+    pub fn rooted(&self) -> &RootedForest {
+        &self.rooted
     }
 
-    pub fn find_path(&self, endpoints: [NodeIndex; 2]) -> Vec<[NodeIndex; 2]> {
-        let mut visited = vec![false; self.forest.nodes_count()];
+    /// Hangs the tree of `root` from the `parent` node through the `edge`,
+    /// which isn't added yet, returns the nodes of the tree.
+    fn hang(&mut self, root: NodeIndex, (edge, parent): (EdgeIndex, NodeIndex)) -> Vec<NodeIndex> {
+        self.rooted.parents[root.0] = Some((edge, parent));
+        self.rooted.depths[root.0] = self.rooted.depths[parent.0] + 1;
+
+        let mut visited = HashSet::from([root]);
+        let mut queue = VecDeque::from([root]);
+        let mut nodes = vec![];
+
+        while let Some(node) = queue.pop_front() {
+            nodes.push(node);
+
+            for (edge, child) in self.forest.incident_edges(node) {
+                if visited.insert(child) {
+                    self.rooted.parents[child.0] = Some((edge, node));
+                    self.rooted.depths[child.0] = self.rooted.depths[node.0] + 1;
+
+                    queue.push_back(child);
+                }
+            }
+        }
 
-        find_path(&self.forest, endpoints, &mut visited).unwrap()
+        nodes
     }
-}
 
-fn find_path(
-    graph: &MatrixGraph,
-    [start, end]: [NodeIndex; 2],
-    visited: &mut Vec<bool>,
-) -> Option<Vec<[NodeIndex; 2]>> {
-    if graph.neighbour_nodes(start).any(|node| node == end) {
-        Some(vec![[end, start]])
-    } else {
-        for new_start in graph.neighbour_nodes(start) {
-            if visited[new_start.0] {
-                continue;
+    fn tree_nodes(&self, start: NodeIndex) -> Vec<NodeIndex> {
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut nodes = vec![];
+
+        while let Some(node) = queue.pop_front() {
+            nodes.push(node);
+
+            for other in self.forest.neighbour_nodes(node) {
+                if visited.insert(other) {
+                    queue.push_back(other);
+                }
             }
+        }
+
+        nodes
+    }
+
+    fn new_label(&mut self) -> usize {
+        self.labels_count += 1;
+        self.labels_count - 1
+    }
+}
+
+/// Parent of every node with the edge to it, depths are only compared within a tree.
+#[derive(Default)]
+pub struct RootedForest {
+    parents: Vec<Option<(EdgeIndex, NodeIndex)>>,
+    depths: Vec<usize>,
+}
+
+impl RootedForest {
+    /// The path from `endpoints[1]` to `endpoints[0]` as traversed edges of the forest,
+    /// both ends climb to their common ancestor.
+    pub fn find_path(&self, [a, b]: [NodeIndex; 2]) -> Vec<(EdgeIndex, [NodeIndex; 2])> {
+        let (mut a, mut b) = (a, b);
+
+        let mut from_b = vec![];
+        let mut from_a = vec![];
 
-            visited[new_start.0] = true;
+        while a != b {
+            if self.depths[b.0] >= self.depths[a.0] {
+                let (edge, parent) = self.parents[b.0].unwrap();
 
-            if let Some(mut path) = find_path(graph, [new_start, end], visited) {
-                path.push([new_start, start]);
+                from_b.push((edge, [b, parent]));
+                b = parent;
+            } else {
+                let (edge, parent) = self.parents[a.0].unwrap();
 
-                return Some(path);
+                from_a.push((edge, [parent, a]));
+                a = parent;
             }
         }
 
-        None
+        from_b.extend(from_a.into_iter().rev());
+        from_b
     }
 }
@@ -0,0 +1,1106 @@
+use super::solver::{LoopSolver, MnaSolver, Solver};
+use super::{Circuit, ElementId, IntegrationMethod, Probe, Recorder};
+use super::{SolveError, SweepParameter, SweepScale, TransientOptions};
+use crate::default_conductors::*;
+use crate::Conductor;
+
+type TestCircuit = Circuit<'static, Box<dyn Conductor<f64>>, usize, f64>;
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance * expected.abs().max(1.0),
+        "{actual} != {expected}"
+    );
+}
+
+fn current(circuit: &TestCircuit, id: ElementId) -> f64 {
+    circuit.operating_point(id).current
+}
+
+fn with_both_solvers() -> [TestCircuit; 2] {
+    let mut mna = TestCircuit::default();
+    let mut loops = TestCircuit::default();
+
+    mna.set_solver(MnaSolver);
+    loops.set_solver(LoopSolver);
+
+    [mna, loops]
+}
+
+/// A bridge of resistors with a source on every side of the diamond.
+fn bridge(solver: impl Solver<f64> + 'static) -> (TestCircuit, Vec<ElementId>) {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_solver(solver);
+    circuit.set_ground(0);
+
+    let ids = vec![
+        circuit.add([0, 1], Box::new(CurrentSource::new(10.0, 1.0))),
+        circuit.add([1, 2], Box::new(Resistor::new(2.0))),
+        circuit.add([1, 3], Box::new(Resistor::new(3.0))),
+        circuit.add([2, 3], Box::new(CurrentSource::new(4.0, 5.0))),
+        circuit.add([2, 0], Box::new(Resistor::new(6.0))),
+        circuit.add([3, 0], Box::new(Resistor::new(7.0))),
+        circuit.add([3, 0], Box::new(IdealCurrentSource::new(0.5))),
+    ];
+
+    circuit.update(0.0);
+
+    (circuit, ids)
+}
+
+#[test]
+fn mna_and_loop_solvers_agree() {
+    let (mna, ids) = bridge(MnaSolver);
+    let (loops, _) = bridge(LoopSolver);
+
+    for &id in &ids {
+        assert_close(current(&loops, id), current(&mna, id), 1e-9);
+    }
+
+    for node in 0..4 {
+        assert_close(
+            loops.voltage(node).unwrap(),
+            mna.voltage(node).unwrap(),
+            1e-9,
+        );
+    }
+}
+
+#[test]
+fn solvers_agree_after_changes() {
+    let (mut mna, ids) = bridge(MnaSolver);
+    let (mut loops, _) = bridge(LoopSolver);
+
+    for circuit in [&mut mna, &mut loops] {
+        // removing forest edges makes the spanning forest reconnect through other edges
+        circuit.remove(ids[1]);
+        circuit.change(ids[2], [2, 3]);
+        circuit.change(ids[4], [2, 0]);
+        circuit.add([1, 2], Box::new(Resistor::new(8.0)));
+        circuit.update(0.0);
+    }
+
+    for node in 0..4 {
+        assert_close(
+            loops.voltage(node).unwrap(),
+            mna.voltage(node).unwrap(),
+            1e-9,
+        );
+    }
+}
+
+#[test]
+fn solvers_agree_after_random_changes() {
+    let [mut mna, mut loops] = with_both_solvers();
+
+    let mut seed = 1u64;
+    let mut random = |count: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        (seed >> 33) as usize % count
+    };
+
+    let mut ids = vec![];
+
+    for circuit in [&mut mna, &mut loops] {
+        circuit.set_ground(0);
+        ids = vec![circuit.add([0, 1], Box::new(CurrentSource::new(10.0, 1.0)))];
+    }
+
+    for _ in 0..300 {
+        let endpoints = [random(8), random(8)];
+        let resistance = 1.0 + random(10) as f64;
+
+        match random(3) {
+            0 => {
+                let id = mna.add(endpoints, Box::new(Resistor::new(resistance)));
+
+                assert_eq!(
+                    loops.add(endpoints, Box::new(Resistor::new(resistance))),
+                    id
+                );
+
+                ids.push(id);
+            }
+            1 if ids.len() > 1 => {
+                let id = ids.swap_remove(1 + random(ids.len() - 1));
+
+                mna.remove(id);
+                loops.remove(id);
+            }
+            _ => {
+                let id = ids[random(ids.len())];
+
+                mna.change(id, endpoints);
+                loops.change(id, endpoints);
+            }
+        }
+
+        mna.update(0.0);
+        loops.update(0.0);
+
+        for &id in &ids {
+            assert_close(current(&loops, id), current(&mna, id), 1e-6);
+        }
+    }
+}
+
+#[test]
+fn operating_point() {
+    let (circuit, ids) = bridge(MnaSolver);
+
+    for (&id, resistance) in ids[4..6].iter().zip([6.0, 7.0]) {
+        let state = circuit.operating_point(id);
+
+        assert_close(state.voltage, state.current * resistance, 1e-9);
+        assert_close(state.power, state.voltage * state.current, 1e-9);
+    }
+}
+
+#[test]
+fn parallel_elements() {
+    for mut circuit in with_both_solvers() {
+        circuit.set_ground(0);
+
+        let source = circuit.add([0, 1], Box::new(CurrentSource::new(12.0, 0.0)));
+        let first = circuit.add([1, 0], Box::new(Resistor::new(4.0)));
+        let second = circuit.add([1, 0], Box::new(Resistor::new(12.0)));
+        let third = circuit.add([1, 0], Box::new(Resistor::new(6.0)));
+
+        circuit.update(0.0);
+
+        assert_close(current(&circuit, first), 3.0, 1e-9);
+        assert_close(current(&circuit, second), 1.0, 1e-9);
+        assert_close(current(&circuit, third), 2.0, 1e-9);
+        assert_close(current(&circuit, source), 6.0, 1e-9);
+    }
+}
+
+#[test]
+fn shorted_source() {
+    for mut circuit in with_both_solvers() {
+        let source = circuit.add([0, 1], Box::new(CurrentSource::new(5.0, 0.0)));
+        circuit.add([1, 2], Box::new(Wire));
+        circuit.add([2, 0], Box::new(Wire));
+
+        assert_eq!(
+            circuit.try_update(0.0).err(),
+            Some(SolveError::ShortedSource(source))
+        );
+    }
+}
+
+#[test]
+fn open_source() {
+    for mut circuit in with_both_solvers() {
+        let source = circuit.add([0, 1], Box::new(IdealCurrentSource::new(1.0)));
+        circuit.add([1, 2], Box::new(Resistor::new(1.0)));
+
+        assert_eq!(
+            circuit.try_update(0.0).err(),
+            Some(SolveError::OpenSource(source))
+        );
+    }
+}
+
+#[test]
+fn unknown_element() {
+    let (mut circuit, ids) = bridge(MnaSolver);
+
+    circuit.remove(ids[0]);
+
+    assert_eq!(
+        circuit.try_remove(ids[0]),
+        Err(SolveError::UnknownElement(ids[0]))
+    );
+    assert!(circuit.try_operating_point(ids[0]).is_err());
+}
+
+const METHODS: [IntegrationMethod; 3] = [
+    IntegrationMethod::BackwardEuler,
+    IntegrationMethod::Trapezoidal,
+    IntegrationMethod::Gear2,
+];
+
+fn transient(circuit: &mut TestCircuit, method: IntegrationMethod) {
+    circuit
+        .set_transient_options(TransientOptions {
+            method,
+            max_step: 1e-5,
+            tolerance: 1e-6,
+        })
+        .unwrap();
+}
+
+#[test]
+fn rc_transient() {
+    for method in METHODS {
+        let mut circuit = TestCircuit::default();
+
+        transient(&mut circuit, method);
+        circuit.set_ground(0);
+
+        circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 0.0)));
+        circuit.add([1, 2], Box::new(Resistor::new(1000.0)));
+        circuit.add([2, 0], Box::new(Capacitor::new(1e-6)));
+
+        // the time constant is 1 ms
+        circuit.update(1e-3);
+
+        let expected = 1.0 - f64::exp(-1.0);
+
+        assert_close(circuit.voltage(2).unwrap(), expected, 1e-3);
+    }
+}
+
+#[test]
+fn rl_transient() {
+    for method in METHODS {
+        let mut circuit = TestCircuit::default();
+
+        transient(&mut circuit, method);
+
+        circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 0.0)));
+        circuit.add([1, 2], Box::new(Resistor::new(1.0)));
+        let inductor = circuit.add([2, 0], Box::new(Inductor::new(1e-3)));
+
+        circuit.update(1e-3);
+
+        let expected = 1.0 - f64::exp(-1.0);
+
+        assert_close(current(&circuit, inductor), expected, 1e-3);
+    }
+}
+
+#[test]
+fn invalid_max_step() {
+    let mut circuit = TestCircuit::default();
+
+    for max_step in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let options = TransientOptions {
+            max_step,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            circuit.set_transient_options(options),
+            Err(SolveError::InvalidStep)
+        );
+    }
+
+    assert_eq!(circuit.transient_options(), TransientOptions::default());
+}
+
+#[test]
+fn time_keeps_advancing() {
+    let mut circuit = Circuit::<Box<dyn Conductor>, usize>::default();
+
+    circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 1.0)));
+    circuit.add([1, 0], Box::new(Resistor::new(1.0)));
+
+    circuit
+        .set_transient_options(TransientOptions {
+            max_step: 4096.0,
+            ..Default::default()
+        })
+        .unwrap();
+    circuit.update(32768.0);
+
+    circuit
+        .set_transient_options(TransientOptions {
+            max_step: 1e-3,
+            ..Default::default()
+        })
+        .unwrap();
+
+    for _ in 0..10 {
+        circuit.update(1e-3);
+    }
+
+    assert!(circuit.time() > 32768.0);
+}
+
+/// A source shorted by a wire after `working` steps, it asks for more substeps every step.
+struct Failing {
+    working: usize,
+    saved: usize,
+}
+
+impl Conductor<f64> for Failing {
+    fn zap(&mut self, _amperage: f64, _delta_time: f64) {
+        self.working = self.working.saturating_sub(1);
+    }
+
+    fn save(&mut self) {
+        self.saved = self.working;
+    }
+
+    fn restore(&mut self) {
+        self.working = self.saved;
+    }
+
+    fn emf(&self) -> f64 {
+        1.0
+    }
+
+    fn resistance(&self) -> f64 {
+        if self.working > 0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn truncation_error(&self) -> Option<f64> {
+        Some(1.0)
+    }
+}
+
+#[test]
+fn completed_substeps_are_kept() {
+    let mut circuit = TestCircuit::default();
+
+    circuit
+        .set_transient_options(TransientOptions {
+            max_step: 1.0,
+            ..Default::default()
+        })
+        .unwrap();
+
+    let failing = Failing {
+        working: 1500,
+        saved: 0,
+    };
+
+    circuit.add([0, 1], Box::new(failing));
+    circuit.add([1, 0], Box::new(Wire));
+
+    // rejected until it's split into the most substeps
+    circuit.update(1.0);
+    assert_eq!(circuit.time(), 1.0);
+
+    // the rest of the substeps work in the next step
+    assert!(circuit.try_update(1.0).is_err());
+    assert_eq!(circuit.time(), 1.0 + 476.0 / 1024.0);
+
+    assert!(circuit.try_update(1.0).is_err());
+    assert_eq!(circuit.time(), 1.0 + 476.0 / 1024.0);
+}
+
+#[test]
+fn inaccurate_step_is_redone() {
+    let mut circuit = TestCircuit::default();
+
+    circuit
+        .set_transient_options(TransientOptions {
+            max_step: 1e-3,
+            tolerance: 1e-6,
+            ..Default::default()
+        })
+        .unwrap();
+
+    // the step is as long as the time constant
+    circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 1000.0)));
+
+    let capacitor = circuit.add([1, 0], Box::new(Capacitor::new(1e-6)));
+
+    circuit.update(1e-3);
+
+    let voltage = circuit.operating_point(capacitor).voltage;
+
+    assert_close(voltage, 1.0 - (-1.0f64).exp(), 1e-3);
+}
+
+#[test]
+fn current_source_through_inductor() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.add([0, 1], Box::new(IdealCurrentSource::new(1.0)));
+    let inductor = circuit.add([1, 0], Box::new(Inductor::new(1e-3)));
+
+    assert!(circuit.try_update(0.0).is_ok());
+    assert_close(current(&circuit, inductor), 1.0, 1e-9);
+}
+
+const THERMAL_VOLTAGE: f64 = 0.025852;
+
+#[test]
+fn diode_operating_point() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    circuit.add([0, 1], Box::new(CurrentSource::new(5.0, 0.0)));
+    let resistor = circuit.add([1, 2], Box::new(Resistor::new(1000.0)));
+    let diode = circuit.add([2, 0], Box::new(Diode::new(1e-14, 1.0)));
+
+    circuit.update(0.0);
+
+    let voltage = circuit.operating_point(diode).voltage;
+    let amperage = current(&circuit, diode);
+
+    assert_close(amperage, current(&circuit, resistor), 1e-9);
+    assert_close(amperage, (5.0 - voltage) / 1000.0, 1e-6);
+    assert_close(
+        amperage,
+        1e-14 * (f64::exp(voltage / THERMAL_VOLTAGE) - 1.0),
+        1e-6,
+    );
+}
+
+#[test]
+fn zener_clamp() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+    circuit.add([0, 1], Box::new(CurrentSource::new(12.0, 0.0)));
+    circuit.add([1, 2], Box::new(Resistor::new(1000.0)));
+    circuit.add([0, 2], Box::new(ZenerDiode::new(1e-14, 1.0, 5.1)));
+    circuit.update(0.0);
+
+    // about 7 mA flows in reverse, 1 mA flows at the breakdown voltage
+    let voltage = circuit.voltage(2).unwrap();
+    let current = (12.0 - voltage) / 1000.0;
+
+    assert_close(voltage, 5.15, 1e-3);
+    assert_close(
+        voltage,
+        5.1 + THERMAL_VOLTAGE * (current / ZENER_BREAKDOWN_CURRENT).ln(),
+        1e-4,
+    );
+}
+
+#[test]
+fn led_forward_voltage() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+    circuit.add(
+        [0, 1],
+        Box::new(IdealCurrentSource::new(LED_NOMINAL_CURRENT)),
+    );
+    circuit.add([1, 0], Box::new(Led::new(2.0, [255, 0, 0])));
+    circuit.update(0.0);
+
+    assert_close(circuit.voltage(1).unwrap(), 2.0, 1e-6);
+}
+
+#[test]
+fn terminals_mismatch() {
+    let mut circuit = TestCircuit::default();
+
+    let resistor = circuit.add([0, 1], Box::new(Resistor::new(1.0)));
+
+    assert_eq!(
+        circuit.try_change_multi(resistor, &[0, 1, 2]),
+        Err(SolveError::TerminalsMismatch {
+            id: resistor,
+            expected: 2
+        })
+    );
+}
+
+/// A 2 V source driving a 1 kΩ resistor, the source under the test feeds a 100 Ω load
+/// from the node 2.
+fn controlled_source(
+    add: impl FnOnce(&mut TestCircuit, ElementId) -> ElementId,
+) -> (TestCircuit, ElementId, ElementId) {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+    circuit.add([0, 1], Box::new(CurrentSource::new(2.0, 0.0)));
+
+    let control = circuit.add([1, 0], Box::new(Resistor::new(1000.0)));
+    let load = circuit.add([2, 0], Box::new(Resistor::new(100.0)));
+    let source = add(&mut circuit, control);
+
+    circuit.update(0.0);
+
+    (circuit, load, source)
+}
+
+#[test]
+fn controlled_sources() {
+    let (circuit, load, _) =
+        controlled_source(|circuit, _| circuit.add_multi(&[2, 0, 1, 0], Box::new(Vcvs::new(3.0))));
+
+    assert_close(circuit.voltage(2).unwrap(), 6.0, 1e-9);
+    assert_close(current(&circuit, load), 0.06, 1e-9);
+
+    let (circuit, load, _) =
+        controlled_source(|circuit, _| circuit.add_multi(&[0, 2, 1, 0], Box::new(Vccs::new(0.01))));
+
+    assert_close(circuit.voltage(2).unwrap(), 2.0, 1e-9);
+    assert_close(current(&circuit, load), 0.02, 1e-9);
+
+    let (circuit, load, _) = controlled_source(|circuit, control| {
+        circuit.add_multi(&[2, 0], Box::new(Ccvs::new(1000.0, Some(control))))
+    });
+
+    assert_close(circuit.voltage(2).unwrap(), 2.0, 1e-9);
+    assert_close(current(&circuit, load), 0.02, 1e-9);
+
+    let (circuit, load, _) = controlled_source(|circuit, control| {
+        circuit.add_multi(&[0, 2], Box::new(Cccs::new(10.0, Some(control))))
+    });
+
+    assert_close(circuit.voltage(2).unwrap(), 2.0, 1e-9);
+    assert_close(current(&circuit, load), 0.02, 1e-9);
+}
+
+#[test]
+fn controlling_element_removal() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+    circuit.add([0, 1], Box::new(CurrentSource::new(2.0, 0.0)));
+
+    let control = circuit.add([1, 0], Box::new(Resistor::new(1000.0)));
+    let source = circuit.add_multi(&[2, 0], Box::new(Ccvs::new(1000.0, Some(control))));
+
+    circuit.add([2, 0], Box::new(Resistor::new(100.0)));
+
+    assert_eq!(
+        circuit.try_remove(control),
+        Err(SolveError::Controlling {
+            id: control,
+            dependent: source
+        })
+    );
+
+    // the circuit is unchanged
+    circuit.update(0.0);
+
+    assert_close(circuit.voltage(2).unwrap(), 2.0, 1e-9);
+
+    circuit.remove(source);
+
+    assert_eq!(circuit.try_remove(control), Ok(()));
+}
+
+#[test]
+fn op_amp_needs_ground() {
+    let mut circuit = TestCircuit::default();
+
+    // non-inverting amplifier with the gain of 2
+    circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 0.0)));
+    circuit.add([3, 2], Box::new(Resistor::new(1000.0)));
+    circuit.add([2, 0], Box::new(Resistor::new(1000.0)));
+    circuit.add_multi(&[1, 2, 3], Box::new(IdealOpAmp));
+
+    assert_eq!(circuit.try_update(0.0).err(), Some(SolveError::Ungrounded));
+    assert_eq!(
+        circuit.try_ac_analysis(0.0).err(),
+        Some(SolveError::Ungrounded)
+    );
+
+    circuit.set_ground(0);
+    circuit.update(0.0);
+
+    assert_close(circuit.voltage(3).unwrap(), 2.0, 1e-9);
+}
+
+#[test]
+fn voltage_follower() {
+    for wired in [false, true] {
+        let mut circuit = TestCircuit::default();
+
+        circuit.set_ground(0);
+        circuit.add([0, 1], Box::new(CurrentSource::new(3.0, 0.0)));
+
+        if wired {
+            circuit.add([2, 3], Box::new(Wire));
+            circuit.add_multi(&[1, 3, 2], Box::new(IdealOpAmp));
+        } else {
+            circuit.add_multi(&[1, 2, 2], Box::new(IdealOpAmp));
+        }
+
+        circuit.update(0.0);
+
+        assert_close(circuit.voltage(2).unwrap(), 3.0, 1e-9);
+
+        let load = circuit.add([2, 0], Box::new(Resistor::new(100.0)));
+
+        circuit.update(0.0);
+
+        assert_close(circuit.voltage(2).unwrap(), 3.0, 1e-9);
+        assert_close(current(&circuit, load), 0.03, 1e-9);
+    }
+}
+
+#[test]
+fn comparator() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    let input = circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 0.0)));
+
+    circuit.add([0, 2], Box::new(CurrentSource::new(0.5, 0.0)));
+    circuit.add_multi(&[1, 2, 3], Box::new(OpAmp::new(1e5, 12.0, -12.0)));
+    circuit.update(0.0);
+
+    assert_close(circuit.voltage(3).unwrap(), 12.0, 1e-6);
+
+    *circuit.get_mut(input) = Box::new(CurrentSource::new(0.0, 0.0));
+    circuit.update(0.0);
+
+    assert_close(circuit.voltage(3).unwrap(), -12.0, 1e-6);
+}
+
+#[test]
+fn op_amp_saturation() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    // non-inverting amplifier with the gain of 2 and the rails at ±12 V
+    let input = circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 0.0)));
+
+    circuit.add([3, 2], Box::new(Resistor::new(1000.0)));
+    circuit.add([2, 0], Box::new(Resistor::new(1000.0)));
+    circuit.add_multi(&[1, 2, 3], Box::new(OpAmp::new(1e5, 12.0, -12.0)));
+    circuit.update(0.0);
+
+    assert_close(circuit.voltage(3).unwrap(), 2.0, 1e-4);
+
+    for (emf, output) in [(10.0, 12.0), (-10.0, -12.0)] {
+        *circuit.get_mut(input) = Box::new(CurrentSource::new(emf, 0.0));
+        circuit.update(0.0);
+
+        assert_close(circuit.voltage(3).unwrap(), output, 1e-6);
+    }
+}
+
+#[test]
+fn bjt_operating_point() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    circuit.add([0, 1], Box::new(CurrentSource::new(10.0, 0.0)));
+    circuit.add([1, 2], Box::new(Resistor::new(1000.0)));
+    circuit.add([1, 3], Box::new(Resistor::new(200_000.0)));
+    let bjt = circuit.add_multi(&[2, 3, 0], Box::new(Bjt::new(Polarity::N, 100.0, 1e-14)));
+
+    circuit.update(0.0);
+
+    let [collector, base, emitter] = circuit.terminal_currents(bjt)[..] else {
+        panic!("the transistor has three terminals");
+    };
+
+    // forward active: the collector current is `beta` times the base one
+    assert_close(collector / base, 100.0, 1e-3);
+    assert_close(collector + base + emitter, 0.0, 1e-9);
+    assert!(circuit.voltage(2).unwrap() > 1.0);
+}
+
+#[test]
+fn mosfet_inverter() {
+    for (input, output) in [(0.0, 5.0), (5.0, 0.0)] {
+        let mut circuit = TestCircuit::default();
+
+        circuit.set_ground(0);
+        circuit.add([0, 1], Box::new(CurrentSource::new(5.0, 0.0)));
+        circuit.add([0, 2], Box::new(CurrentSource::new(input, 0.0)));
+        circuit.add_multi(&[3, 2, 1], Box::new(Mosfet::new(Polarity::P, 1.0, 1e-3)));
+        circuit.add_multi(&[3, 2, 0], Box::new(Mosfet::new(Polarity::N, 1.0, 1e-3)));
+        circuit.add([3, 0], Box::new(Resistor::new(1e6)));
+        circuit.update(0.0);
+
+        assert!((circuit.voltage(3).unwrap() - output).abs() < 1e-2);
+    }
+}
+
+#[test]
+fn mosfet_saturation_current() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+    circuit.add([0, 1], Box::new(CurrentSource::new(5.0, 0.0)));
+    circuit.add([0, 2], Box::new(CurrentSource::new(2.0, 0.0)));
+    circuit.add([1, 3], Box::new(Resistor::new(1000.0)));
+
+    let mosfet = circuit.add_multi(&[3, 2, 0], Box::new(Mosfet::new(Polarity::N, 1.0, 1e-3)));
+
+    circuit.update(0.0);
+
+    // 1e-3 / 2 * (2 - 1)^2
+    assert_close(circuit.voltage(3).unwrap(), 4.5, 1e-9);
+    assert_close(circuit.terminal_currents(mosfet)[0], 5e-4, 1e-9);
+}
+
+#[test]
+fn dc_sources_in_ac_analysis() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    let sine = Waveform::Sine {
+        amplitude: 1.0,
+        frequency: 50.0,
+        phase: 0.0,
+        offset: 0.0,
+    };
+
+    circuit.add([0, 1], Box::new(CurrentSource::new(5.0, 0.0)));
+    circuit.add([1, 2], Box::new(FunctionSource::new(sine, 0.0)));
+    circuit.add([2, 0], Box::new(Resistor::new(1000.0)));
+
+    let solution = circuit.ac_analysis(50.0);
+
+    assert_close(solution.voltage(2).unwrap().re, 1.0, 1e-9);
+    assert_close(solution.voltage(2).unwrap().im, 0.0, 1e-9);
+}
+
+#[test]
+fn log_sweep_from_zero() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    let input = circuit.add([0, 1], Box::new(CurrentSource::new(1.0, 0.0)));
+    circuit.add([1, 2], Box::new(Resistor::new(1000.0)));
+    circuit.add([2, 0], Box::new(Capacitor::new(1e-6)));
+
+    let sweep = circuit.try_frequency_sweep(0.0..=1000.0, 10, SweepScale::Log, input, 2);
+
+    assert_eq!(sweep.err(), Some(SolveError::InvalidRange));
+
+    let sweep = circuit.frequency_sweep(1.0..=1000.0, 10, SweepScale::Log, input, 2);
+
+    assert!(sweep.iter().all(|point| point.gain.is_finite()));
+}
+
+/// A 10 V divider of two 1 kΩ resistors with the middle at the node 2.
+fn divider() -> (TestCircuit, [ElementId; 3]) {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    let ids = [
+        circuit.add([0, 1], Box::new(CurrentSource::new(10.0, 0.0))),
+        circuit.add([1, 2], Box::new(Resistor::new(1000.0))),
+        circuit.add([2, 0], Box::new(Resistor::new(1000.0))),
+    ];
+
+    circuit.update(0.0);
+
+    (circuit, ids)
+}
+
+#[test]
+fn dc_sweep() {
+    let (mut circuit, [source, _, lower]) = divider();
+
+    let probes = [Probe::Voltage(2), Probe::Current(lower)];
+    let points = circuit.dc_sweep(source, SweepParameter::Emf, 0.0, 10.0, 4, &probes);
+
+    assert_eq!(points.len(), 5);
+
+    for (point, emf) in points.iter().zip([0.0, 2.5, 5.0, 7.5, 10.0]) {
+        assert_close(point.value, emf, 1e-12);
+        assert_close(point.readings[0], emf / 2.0, 1e-9);
+        assert_close(point.readings[1], emf / 2000.0, 1e-9);
+    }
+
+    let points = circuit.dc_sweep(
+        lower,
+        SweepParameter::Resistance,
+        1000.0,
+        3000.0,
+        2,
+        &probes,
+    );
+
+    for (point, resistance) in points.iter().zip([1000.0, 2000.0, 3000.0]) {
+        assert_close(
+            point.readings[0],
+            10.0 * resistance / (1000.0 + resistance),
+            1e-9,
+        );
+    }
+
+    // the state of the circuit is kept
+    assert_close(circuit.voltage(2).unwrap(), 5.0, 1e-9);
+    assert_close(current(&circuit, lower), 5e-3, 1e-9);
+}
+
+#[test]
+fn dc_sweep_of_current_source() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    let source = circuit.add([0, 1], Box::new(IdealCurrentSource::new(1e-3)));
+    circuit.add([1, 0], Box::new(Resistor::new(1000.0)));
+
+    let points = circuit.dc_sweep(
+        source,
+        SweepParameter::Amperage,
+        -1e-3,
+        1e-3,
+        2,
+        &[Probe::Voltage(1)],
+    );
+
+    for (point, voltage) in points.iter().zip([-1.0, 0.0, 1.0]) {
+        assert_close(point.readings[0], voltage, 1e-9);
+    }
+}
+
+#[test]
+fn dc_sweep_errors() {
+    let (mut circuit, [source, upper, _]) = divider();
+
+    let diode = circuit.add([2, 0], Box::new(Diode::new(1e-14, 1.0)));
+    let op_amp = circuit.add_multi(&[2, 3, 3], Box::new(IdealOpAmp));
+    let removed = circuit.add([2, 0], Box::new(Resistor::new(1.0)));
+
+    circuit.remove(removed);
+
+    let cases = [
+        (
+            source,
+            SweepParameter::Amperage,
+            SolveError::Unsupported(source),
+        ),
+        (
+            upper,
+            SweepParameter::Amperage,
+            SolveError::Unsupported(upper),
+        ),
+        (diode, SweepParameter::Emf, SolveError::Unsupported(diode)),
+        (op_amp, SweepParameter::Emf, SolveError::Unsupported(op_amp)),
+        (
+            removed,
+            SweepParameter::Emf,
+            SolveError::UnknownElement(removed),
+        ),
+    ];
+
+    for (element, parameter, error) in cases {
+        let sweep = circuit.try_dc_sweep(element, parameter, 0.0, 1.0, 2, &[Probe::Voltage(2)]);
+
+        assert_eq!(sweep, Err(error));
+    }
+}
+
+#[test]
+fn waveforms() {
+    let square = Waveform::Square {
+        amplitude: 1.0,
+        frequency: 10.0,
+        duty_cycle: 0.25,
+        offset: 2.0,
+    };
+
+    let triangle = Waveform::Triangle {
+        amplitude: 1.0,
+        frequency: 10.0,
+        offset: 0.0,
+    };
+
+    let pulse = Waveform::Pulse {
+        low: 0.0,
+        high: 5.0,
+        delay: 1.0,
+        rise: 0.0,
+        width: 2.0,
+        fall: 1.0,
+        period: 10.0,
+    };
+
+    let pwl = Waveform::PiecewiseLinear(vec![(1.0, 2.0), (3.0, 6.0), (4.0, -1.0)]);
+
+    let cases = [
+        // the high part of the square lasts a quarter of the period
+        (&square, 0.0, 3.0),
+        (&square, 0.024, 3.0),
+        (&square, 0.026, 1.0),
+        (&square, 0.099, 1.0),
+        (&square, 0.124, 3.0),
+        // the triangle rises in the first half of the period with the slope 40
+        (&triangle, 0.0, -1.0),
+        (&triangle, 0.0125, -0.5),
+        (&triangle, 0.025, 0.0),
+        (&triangle, 0.05, 1.0),
+        (&triangle, 0.075, 0.0),
+        (&triangle, 0.1125, -0.5),
+        // with no rise time the pulse jumps at the delay
+        (&pulse, 0.999, 0.0),
+        (&pulse, 1.0, 5.0),
+        (&pulse, 2.999, 5.0),
+        (&pulse, 3.5, 2.5),
+        (&pulse, 4.0, 0.0),
+        (&pulse, 11.0, 5.0),
+        // the first and the last points are held outside of the curve
+        (&pwl, -5.0, 2.0),
+        (&pwl, 1.0, 2.0),
+        (&pwl, 2.0, 4.0),
+        (&pwl, 3.5, 2.5),
+        (&pwl, 4.0, -1.0),
+        (&pwl, 100.0, -1.0),
+    ];
+
+    for (waveform, time, value) in cases {
+        assert_close(waveform.value(time), value, 1e-9);
+    }
+
+    assert_eq!(Waveform::PiecewiseLinear(vec![]).value(1.0), 0.0);
+}
+
+#[test]
+fn recorder_capacity() {
+    let mut recorder = Recorder::<usize, f64>::new(3);
+
+    recorder.add_probe(Probe::Voltage(0));
+
+    for time in 0..5 {
+        recorder.push(time as f64, vec![Some(time as f64 * 10.0)]);
+    }
+
+    // the oldest samples are evicted
+    let times: Vec<_> = recorder.samples().map(|sample| sample.time).collect();
+
+    assert_eq!(times, [2.0, 3.0, 4.0]);
+
+    recorder.set_capacity(2);
+
+    let channel: Vec<_> = recorder.channel(Probe::Voltage(0)).collect();
+
+    assert_eq!(channel, [(3.0, 30.0), (4.0, 40.0)]);
+
+    recorder.set_capacity(0);
+    recorder.push(5.0, vec![Some(50.0)]);
+
+    assert_eq!(recorder.samples().len(), 0);
+}
+
+#[test]
+fn recorder_probes() {
+    let mut circuit = TestCircuit::default();
+
+    circuit.set_ground(0);
+
+    let source = circuit.add([0, 1], Box::new(CurrentSource::new(10.0, 0.0)));
+    circuit.add([1, 2], Box::new(Resistor::new(1000.0)));
+    circuit.add([2, 0], Box::new(Resistor::new(1000.0)));
+
+    let recorder = circuit.recorder_mut();
+
+    recorder.add_probe(Probe::Voltage(2));
+    recorder.add_probe(Probe::Current(source));
+    recorder.add_probe(Probe::Voltage(5));
+
+    // a sample is taken at the time of every update
+    for delta_time in [1e-3, 1e-3, 5e-4] {
+        circuit.update(delta_time);
+    }
+
+    let recorder = circuit.recorder();
+    let times: Vec<_> = recorder.samples().map(|sample| sample.time).collect();
+
+    assert_eq!(recorder.samples().len(), 3);
+
+    for (time, expected) in times.into_iter().zip([1e-3, 2e-3, 2.5e-3]) {
+        assert_close(time, expected, 1e-12);
+    }
+
+    let sample = recorder.samples().last().unwrap();
+
+    assert_close(sample.readings[0].unwrap(), 5.0, 1e-9);
+    assert_close(sample.readings[1].unwrap(), 5e-3, 1e-9);
+    assert_eq!(sample.readings[2], None);
+
+    let recorder = circuit.recorder_mut();
+
+    recorder.remove_probe(Probe::Current(source));
+
+    assert_eq!(recorder.probes(), [Probe::Voltage(2), Probe::Voltage(5)]);
+    assert!(recorder.samples().all(|sample| sample.readings.len() == 2));
+    assert_eq!(recorder.channel(Probe::Voltage(5)).count(), 0);
+    assert_eq!(recorder.channel(Probe::Current(source)).count(), 0);
+}
+
+#[test]
+fn recorder_csv() {
+    let mut recorder = Recorder::<usize, f64>::new(4);
+
+    recorder.add_probe(Probe::Voltage(1));
+    recorder.add_probe(Probe::Power(ElementId(2)));
+    recorder.push(0.5, vec![Some(1.25), None]);
+    recorder.push(1.0, vec![Some(-3.0), Some(4.5)]);
+
+    let mut csv = vec![];
+
+    recorder.write_csv(&mut csv).unwrap();
+
+    let csv = String::from_utf8(csv).unwrap();
+    let mut lines = csv.lines();
+
+    assert_eq!(lines.next(), Some("time,V(1),P(2)"));
+
+    // the readings are read back
+    let rows: Vec<Vec<Option<f64>>> = lines
+        .map(|line| line.split(',').map(|field| field.parse().ok()).collect())
+        .collect();
+
+    let samples: Vec<_> = recorder
+        .samples()
+        .map(|sample| {
+            let mut row = vec![Some(sample.time)];
+
+            row.extend(&sample.readings);
+            row
+        })
+        .collect();
+
+    assert_eq!(rows, samples);
+}
+
+#[test]
+fn csv_header_quoting() {
+    let mut recorder = Recorder::<_, f64>::new(4);
+
+    recorder.add_probe(Probe::Voltage("a"));
+    recorder.add_probe(Probe::Current(ElementId(0)));
+
+    let mut csv = vec![];
+
+    recorder.write_csv(&mut csv).unwrap();
+
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "time,\"V(\"\"a\"\")\",I(0)\n"
+    );
+}
+
+#[test]
+fn joined_grounds() {
+    for mut circuit in with_both_solvers() {
+        circuit.set_ground(0);
+        circuit.set_ground(3);
+
+        circuit.add([0, 1], Box::new(CurrentSource::new(10.0, 0.0)));
+        circuit.add([1, 2], Box::new(Resistor::new(1000.0)));
+        let resistor = circuit.add([2, 3], Box::new(Resistor::new(1000.0)));
+
+        circuit.update(0.0);
+
+        assert_close(circuit.voltage(2).unwrap(), 5.0, 1e-9);
+        assert_close(circuit.voltage(3).unwrap(), 0.0, 1e-9);
+        assert_close(current(&circuit, resistor), 5e-3, 1e-9);
+
+        // a source between the grounds is shorted by them
+        let source = circuit.add([3, 0], Box::new(CurrentSource::new(1.0, 0.0)));
+
+        assert_eq!(
+            circuit.try_update(0.0).err(),
+            Some(SolveError::ShortedSource(source))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use nalgebra::RealField;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum IntegrationMethod {
+    #[default]
+    BackwardEuler,
+    Trapezoidal,
+    /// Second order backward differentiation formula.
+    Gear2,
+}
+
+/// How [`Circuit::update`](super::Circuit::update) advances the time.
+///
+/// The time is split into steps of `max_step`, so the results don't depend on how it's
+/// passed to the circuit. A step whose error estimated by reactive elements exceeds
+/// `tolerance` is undone and redone in more substeps.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TransientOptions<T = f32> {
+    pub method: IntegrationMethod,
+    pub max_step: T,
+    pub tolerance: T,
+}
+
+impl<T: RealField + Copy> Default for TransientOptions<T> {
+    fn default() -> Self {
+        Self {
+            method: IntegrationMethod::default(),
+            max_step: nalgebra::convert(1e-3),
+            tolerance: nalgebra::convert(1e-3),
+        }
+    }
+}
+
+/// Integrates `y' = x` for companion models of reactive elements:
+/// during a step the next value is `history + gain * x`, where `x` is found by the solver.
+#[derive(Clone, Copy, Debug)]
+pub struct Integrator<T = f32> {
+    value: T,
+    previous: Option<T>,
+    last_step: T,
+    derivatives: [T; 2],
+
+    step: T,
+    method: IntegrationMethod,
+    error: T,
+}
+
+impl<T: RealField + Copy> Integrator<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            previous: None,
+            last_step: T::zero(),
+            derivatives: [T::zero(); 2],
+            step: T::zero(),
+            method: IntegrationMethod::default(),
+            error: T::zero(),
+        }
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn prepare(&mut self, step: T, method: IntegrationMethod) {
+        self.step = step;
+        self.method = method;
+    }
+
+    /// `(history, gain)` of the prepared step.
+    pub fn companion(&self) -> (T, T) {
+        let [derivative, _] = self.derivatives;
+
+        match (self.method, self.previous) {
+            // the derivative is unknown before the first step
+            (IntegrationMethod::Trapezoidal, Some(_)) => {
+                let gain = self.step / (T::one() + T::one());
+
+                (self.value + gain * derivative, gain)
+            }
+            (IntegrationMethod::Gear2, Some(previous)) if !self.last_step.is_zero() => {
+                let ratio = self.step / self.last_step;
+                let denominator = T::one() + ratio + ratio;
+
+                let history = (T::one() + ratio).powi(2) / denominator * self.value
+                    - ratio.powi(2) / denominator * previous;
+
+                (history, (T::one() + ratio) / denominator * self.step)
+            }
+            _ => (self.value, self.step),
+        }
+    }
+
+    /// Completes the prepared step with the derivative found by the solver.
+    pub fn advance(&mut self, derivative: T) {
+        let (history, gain) = self.companion();
+        let [last, before_last] = self.derivatives;
+
+        let second_difference = (derivative - last - last + before_last).abs();
+
+        self.error = match self.method {
+            IntegrationMethod::BackwardEuler => {
+                self.step * (derivative - last).abs() / nalgebra::convert(2.0)
+            }
+            IntegrationMethod::Trapezoidal => {
+                self.step * second_difference / nalgebra::convert(12.0)
+            }
+            IntegrationMethod::Gear2 => {
+                self.step * second_difference * nalgebra::convert(2.0 / 9.0)
+            }
+        };
+
+        self.previous = Some(self.value);
+        self.last_step = self.step;
+        self.value = history + gain * derivative;
+        self.derivatives = [derivative, last];
+    }
+
+    /// Estimated local truncation error of the last step.
+    pub fn error(&self) -> T {
+        self.error
+    }
+}
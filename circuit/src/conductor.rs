@@ -1,6 +1,114 @@
-pub trait Conductor {
-    fn zap(&mut self, _amperage: f32, _delta_time: f32) {}
+use nalgebra::{Complex, RealField};
 
-    fn emf(&self) -> f32;
-    fn resistance(&self) -> f32;
+use crate::circuit::solver::Stamp;
+use crate::circuit::{AcModel, ElementId, IntegrationMethod};
+
+pub trait Conductor<T: RealField + Copy = f32> {
+    /// Called before the circuit is solved for the next `delta_time`,
+    /// reactive elements set up their companion models here.
+    fn prepare(&mut self, _delta_time: T, _method: IntegrationMethod) {}
+
+    /// `amperage` flows into the first terminal of a multi-terminal element.
+    fn zap(&mut self, _amperage: T, _delta_time: T) {}
+
+    /// Keeps the state changed by [`Conductor::zap`], so a rejected step can be undone.
+    fn save(&mut self) {}
+
+    /// Returns to the state kept by the last [`Conductor::save`].
+    fn restore(&mut self) {}
+
+    fn emf(&self) -> T;
+    fn resistance(&self) -> T;
+
+    /// The current forced through the element, if it's an ideal current source
+    /// its emf and resistance are ignored.
+    fn amperage(&self) -> Option<T> {
+        None
+    }
+
+    /// Conductance and current of a nonlinear element at the voltage drop across it,
+    /// its emf and resistance are ignored. The conductance must not be zero.
+    fn linearize(&self, _voltage: T) -> Option<(T, T)> {
+        None
+    }
+
+    /// Limits the change of the voltage between iterations, so elements with
+    /// steep characteristics don't overshoot.
+    fn limit(&self, voltage: T, _previous: T) -> T {
+        voltage
+    }
+
+    /// Limits the change of the terminal potentials of an element added with
+    /// [`Circuit::add_multi`](crate::Circuit::add_multi) between iterations,
+    /// by default the voltage between the first two terminals is limited with [`Conductor::limit`].
+    fn limit_potentials(&self, potentials: &mut [T], previous: &[T]) {
+        let voltage = self.limit(potentials[0] - potentials[1], previous[0] - previous[1]);
+
+        potentials[1] = potentials[0] - voltage;
+    }
+
+    /// Phasor model of the element at `angular_frequency` for
+    /// [`Circuit::ac_analysis`](crate::Circuit::ac_analysis), nonlinear elements are linearized
+    /// at the `voltage` of the last update. By default sources drive the circuit with
+    /// their emf or amperage only at zero frequency and other elements are resistive.
+    fn ac_model(&self, angular_frequency: T, voltage: T) -> AcModel<T> {
+        let excitation = |value: T| {
+            Complex::from(if angular_frequency.is_zero() {
+                value
+            } else {
+                T::zero()
+            })
+        };
+
+        if let Some(amperage) = self.amperage() {
+            return AcModel::Current(excitation(amperage));
+        }
+
+        match self.linearize(voltage) {
+            Some((conductance, _)) => AcModel::impedance(Complex::from(conductance.recip())),
+            None => AcModel::Impedance {
+                emf: excitation(self.emf()),
+                impedance: Complex::from(self.resistance()),
+            },
+        }
+    }
+
+    /// Estimated local error of the last step of a reactive element.
+    fn truncation_error(&self) -> Option<T> {
+        None
+    }
+
+    /// The element whose current controls this one, it can't be removed
+    /// while this element is in the circuit.
+    fn control(&self) -> Option<ElementId> {
+        None
+    }
+
+    /// Stamps an element added with [`Circuit::add_multi`](crate::Circuit::add_multi)
+    /// into the system, by default it's a branch between the first two terminals.
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        if let Some(amperage) = self.amperage() {
+            stamp.current([0, 1], amperage);
+
+            return;
+        }
+
+        let voltage = stamp.previous_voltage([0, 1]);
+
+        let (emf, resistance) = match self.linearize(voltage) {
+            Some((conductance, current)) => {
+                stamp.set_nonlinear();
+
+                (current / conductance - voltage, conductance.recip())
+            }
+            None => (self.emf(), self.resistance()),
+        };
+
+        if resistance.is_zero() {
+            stamp.voltage_source([0, 1], -emf);
+        } else {
+            stamp.conductance([0, 1], resistance.recip());
+            stamp.current([0, 1], emf / resistance);
+        }
+    }
 }
@@ -1,54 +1,1051 @@
+use nalgebra::{Complex, RealField};
+
+use crate::circuit::solver::{Stamp, Unknown};
+use crate::circuit::{AcModel, ElementId, IntegrationMethod, Integrator};
 use crate::Conductor;
 
 pub struct Wire;
 
-impl Conductor for Wire {
-    fn emf(&self) -> f32 {
-        0.0
+impl<T: RealField + Copy> Conductor<T> for Wire {
+    fn emf(&self) -> T {
+        T::zero()
     }
 
-    fn resistance(&self) -> f32 {
-        0.0
+    fn resistance(&self) -> T {
+        T::zero()
     }
 }
 
-pub struct Resistor {
-    pub resistance: f32,
+pub struct Resistor<T = f32> {
+    pub resistance: T,
 }
 
-impl Conductor for Resistor {
-    fn emf(&self) -> f32 {
-        0.0
+impl<T: RealField + Copy> Conductor<T> for Resistor<T> {
+    fn emf(&self) -> T {
+        T::zero()
     }
 
-    fn resistance(&self) -> f32 {
+    fn resistance(&self) -> T {
         self.resistance
     }
 }
 
-impl Resistor {
-    pub fn new(resistance: f32) -> Self {
+impl<T> Resistor<T> {
+    pub fn new(resistance: T) -> Self {
         Self { resistance }
     }
 }
 
-pub struct CurrentSource {
-    pub emf: f32,
-    pub resistance: f32,
+pub struct CurrentSource<T = f32> {
+    pub emf: T,
+    pub resistance: T,
 }
 
-impl Conductor for CurrentSource {
-    fn emf(&self) -> f32 {
+impl<T: RealField + Copy> Conductor<T> for CurrentSource<T> {
+    fn emf(&self) -> T {
         self.emf
     }
 
-    fn resistance(&self) -> f32 {
+    fn resistance(&self) -> T {
         self.resistance
     }
 }
 
-impl CurrentSource {
-    pub fn new(emf: f32, resistance: f32) -> Self {
+impl<T> CurrentSource<T> {
+    pub fn new(emf: T, resistance: T) -> Self {
         Self { emf, resistance }
     }
 }
+
+/// Emf of a [`FunctionSource`] over time, frequencies are in hertz and phases in radians.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Waveform<T = f32> {
+    /// `offset + amplitude * sin(2π * frequency * t + phase)`.
+    Sine {
+        amplitude: T,
+        frequency: T,
+        phase: T,
+        offset: T,
+    },
+    /// `offset + amplitude` during the `duty_cycle` fraction of a period and `offset - amplitude` after it.
+    Square {
+        amplitude: T,
+        frequency: T,
+        duty_cycle: T,
+        offset: T,
+    },
+    /// Rises from `offset - amplitude` to `offset + amplitude` during the first half of a period.
+    Triangle {
+        amplitude: T,
+        frequency: T,
+        offset: T,
+    },
+    /// `low` until `delay`, then every `period` it rises to `high` during `rise`,
+    /// stays there for `width` and falls back during `fall`.
+    Pulse {
+        low: T,
+        high: T,
+        delay: T,
+        rise: T,
+        width: T,
+        fall: T,
+        period: T,
+    },
+    /// Linear interpolation between `(time, value)` points sorted by time,
+    /// the first and the last values are held outside of them.
+    PiecewiseLinear(Vec<(T, T)>),
+}
+
+impl<T: RealField + Copy> Waveform<T> {
+    pub fn value(&self, time: T) -> T {
+        match *self {
+            Waveform::Sine {
+                amplitude,
+                frequency,
+                phase,
+                offset,
+            } => offset + amplitude * (T::two_pi() * frequency * time + phase).sin(),
+            Waveform::Square {
+                amplitude,
+                frequency,
+                duty_cycle,
+                offset,
+            } => {
+                if (frequency * time).fract() < duty_cycle {
+                    offset + amplitude
+                } else {
+                    offset - amplitude
+                }
+            }
+            Waveform::Triangle {
+                amplitude,
+                frequency,
+                offset,
+            } => {
+                let two = T::one() + T::one();
+                let fraction = (frequency * time).fract();
+
+                offset + amplitude * (T::one() - two * (two * fraction - T::one()).abs())
+            }
+            Waveform::Pulse {
+                low,
+                high,
+                delay,
+                rise,
+                width,
+                fall,
+                period,
+            } => {
+                if time < delay {
+                    return low;
+                }
+
+                let mut time = time - delay;
+
+                if period > T::zero() {
+                    time -= (time / period).floor() * period;
+                }
+
+                if time < rise {
+                    low + (high - low) * time / rise
+                } else if time < rise + width {
+                    high
+                } else if time < rise + width + fall {
+                    high - (high - low) * (time - rise - width) / fall
+                } else {
+                    low
+                }
+            }
+            Waveform::PiecewiseLinear(ref points) => {
+                let next = points.partition_point(|&(point, _)| point <= time);
+
+                match (next.checked_sub(1).map(|i| points[i]), points.get(next)) {
+                    (Some((t0, v0)), Some(&(t1, v1))) => v0 + (v1 - v0) * (time - t0) / (t1 - t0),
+                    (Some((_, value)), None) | (None, Some(&(_, value))) => value,
+                    (None, None) => T::zero(),
+                }
+            }
+        }
+    }
+}
+
+/// A source whose emf follows the `waveform`, its time advances with the steps it's zapped with.
+pub struct FunctionSource<T = f32> {
+    pub waveform: Waveform<T>,
+    pub resistance: T,
+
+    time: T,
+    delta_time: T,
+    saved_time: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for FunctionSource<T> {
+    fn prepare(&mut self, delta_time: T, _method: IntegrationMethod) {
+        self.delta_time = delta_time;
+    }
+
+    fn zap(&mut self, _amperage: T, delta_time: T) {
+        self.time += delta_time;
+    }
+
+    fn save(&mut self) {
+        self.saved_time = self.time;
+    }
+
+    fn restore(&mut self) {
+        self.time = self.saved_time;
+    }
+
+    // the value at the end of the step
+    fn emf(&self) -> T {
+        self.waveform.value(self.time + self.delta_time)
+    }
+
+    fn resistance(&self) -> T {
+        self.resistance
+    }
+
+    // only a sine drives the circuit at nonzero frequencies, at zero frequency the source keeps its emf
+    fn ac_model(&self, angular_frequency: T, _voltage: T) -> AcModel<T> {
+        let emf = match self.waveform {
+            _ if angular_frequency.is_zero() => Complex::from(self.waveform.value(self.time)),
+            Waveform::Sine {
+                amplitude, phase, ..
+            } => Complex::new(amplitude * phase.cos(), amplitude * phase.sin()),
+            _ => Complex::from(T::zero()),
+        };
+
+        AcModel::Impedance {
+            emf,
+            impedance: Complex::from(self.resistance),
+        }
+    }
+}
+
+impl<T: RealField + Copy> FunctionSource<T> {
+    pub fn new(waveform: Waveform<T>, resistance: T) -> Self {
+        Self {
+            waveform,
+            resistance,
+            time: T::zero(),
+            delta_time: T::zero(),
+            saved_time: T::zero(),
+        }
+    }
+
+    /// Time passed since the source was created.
+    pub fn time(&self) -> T {
+        self.time
+    }
+}
+
+/// During a step it's a source of the voltage it would have without a current
+/// with the resistance proportional to `delta_time / capacitance`.
+pub struct Capacitor<T = f32> {
+    pub capacitance: T,
+
+    voltage: Integrator<T>,
+    saved: Integrator<T>,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Capacitor<T> {
+    fn prepare(&mut self, delta_time: T, method: IntegrationMethod) {
+        self.voltage.prepare(delta_time, method);
+    }
+
+    fn zap(&mut self, amperage: T, _delta_time: T) {
+        self.voltage.advance(amperage / self.capacitance);
+    }
+
+    fn save(&mut self) {
+        self.saved = self.voltage;
+    }
+
+    fn restore(&mut self) {
+        self.voltage = self.saved;
+    }
+
+    fn emf(&self) -> T {
+        -self.voltage.companion().0
+    }
+
+    fn resistance(&self) -> T {
+        self.voltage.companion().1 / self.capacitance
+    }
+
+    // open at zero frequency
+    fn ac_model(&self, angular_frequency: T, _voltage: T) -> AcModel<T> {
+        if angular_frequency.is_zero() {
+            return AcModel::Current(Complex::from(T::zero()));
+        }
+
+        let reactance = -(angular_frequency * self.capacitance).recip();
+
+        AcModel::impedance(Complex::new(T::zero(), reactance))
+    }
+
+    fn truncation_error(&self) -> Option<T> {
+        Some(self.voltage.error())
+    }
+}
+
+impl<T: RealField + Copy> Capacitor<T> {
+    pub fn new(capacitance: T) -> Self {
+        Self {
+            capacitance,
+            voltage: Integrator::new(T::zero()),
+            saved: Integrator::new(T::zero()),
+        }
+    }
+
+    pub fn voltage(&self) -> T {
+        self.voltage.value()
+    }
+
+    /// Charges the capacitor forgetting its history.
+    pub fn set_voltage(&mut self, voltage: T) {
+        self.voltage = Integrator::new(voltage);
+    }
+}
+
+/// During a step it's a source keeping the current it would have without a voltage
+/// with the resistance proportional to `inductance / delta_time`.
+pub struct Inductor<T = f32> {
+    pub inductance: T,
+
+    current: Integrator<T>,
+    saved: Integrator<T>,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Inductor<T> {
+    fn prepare(&mut self, delta_time: T, method: IntegrationMethod) {
+        self.current.prepare(delta_time, method);
+    }
+
+    fn zap(&mut self, amperage: T, _delta_time: T) {
+        let (history, gain) = self.current.companion();
+
+        self.current.advance((amperage - history) / gain);
+    }
+
+    fn save(&mut self) {
+        self.saved = self.current;
+    }
+
+    fn restore(&mut self) {
+        self.current = self.saved;
+    }
+
+    fn emf(&self) -> T {
+        let (history, _) = self.current.companion();
+
+        self.resistance() * history
+    }
+
+    fn resistance(&self) -> T {
+        self.inductance / self.current.companion().1
+    }
+
+    fn ac_model(&self, angular_frequency: T, _voltage: T) -> AcModel<T> {
+        AcModel::impedance(Complex::new(T::zero(), angular_frequency * self.inductance))
+    }
+
+    fn truncation_error(&self) -> Option<T> {
+        Some(self.current.error())
+    }
+}
+
+impl<T: RealField + Copy> Inductor<T> {
+    pub fn new(inductance: T) -> Self {
+        Self {
+            inductance,
+            current: Integrator::new(T::zero()),
+            saved: Integrator::new(T::zero()),
+        }
+    }
+
+    pub fn current(&self) -> T {
+        self.current.value()
+    }
+
+    pub fn set_current(&mut self, current: T) {
+        self.current = Integrator::new(current);
+    }
+}
+
+/// Shockley diode: `I = saturation_current * (exp(V / (emission_coefficient * V_T)) - 1)`.
+pub struct Diode<T = f32> {
+    pub saturation_current: T,
+    pub emission_coefficient: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Diode<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn linearize(&self, voltage: T) -> Option<(T, T)> {
+        let (conductance, current) =
+            junction(voltage, self.saturation_current, self.emission_coefficient);
+
+        Some(with_min_conductance(
+            voltage,
+            conductance,
+            current - self.saturation_current,
+        ))
+    }
+
+    fn limit(&self, voltage: T, previous: T) -> T {
+        limit_junction(
+            voltage,
+            previous,
+            self.saturation_current,
+            self.emission_coefficient,
+        )
+    }
+}
+
+impl<T> Diode<T> {
+    pub fn new(saturation_current: T, emission_coefficient: T) -> Self {
+        Self {
+            saturation_current,
+            emission_coefficient,
+        }
+    }
+}
+
+/// A diode conducting `LED_NOMINAL_CURRENT` at `forward_voltage`, remembers its current
+/// to shine with.
+pub struct Led<T = f32> {
+    pub forward_voltage: T,
+    pub color: [u8; 3],
+
+    current: T,
+}
+
+pub const LED_NOMINAL_CURRENT: f64 = 0.02;
+const LED_EMISSION_COEFFICIENT: f64 = 2.0;
+
+impl<T: RealField + Copy> Conductor<T> for Led<T> {
+    fn zap(&mut self, amperage: T, _delta_time: T) {
+        self.current = amperage;
+    }
+
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn linearize(&self, voltage: T) -> Option<(T, T)> {
+        self.diode().linearize(voltage)
+    }
+
+    fn limit(&self, voltage: T, previous: T) -> T {
+        self.diode().limit(voltage, previous)
+    }
+}
+
+impl<T: RealField + Copy> Led<T> {
+    pub fn new(forward_voltage: T, color: [u8; 3]) -> Self {
+        Self {
+            forward_voltage,
+            color,
+            current: T::zero(),
+        }
+    }
+
+    pub fn current(&self) -> T {
+        self.current
+    }
+
+    fn diode(&self) -> Diode<T> {
+        let emission_coefficient = nalgebra::convert(LED_EMISSION_COEFFICIENT);
+        let thermal_voltage = emission_coefficient * nalgebra::convert(THERMAL_VOLTAGE);
+
+        let saturation_current = nalgebra::convert::<_, T>(LED_NOMINAL_CURRENT)
+            / ((self.forward_voltage / thermal_voltage).exp() - T::one());
+
+        Diode::new(saturation_current, emission_coefficient)
+    }
+}
+
+/// A diode which also conducts in reverse, `ZENER_BREAKDOWN_CURRENT` flows at `breakdown_voltage`.
+pub struct ZenerDiode<T = f32> {
+    pub saturation_current: T,
+    pub emission_coefficient: T,
+    pub breakdown_voltage: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for ZenerDiode<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn linearize(&self, voltage: T) -> Option<(T, T)> {
+        let (forward_conductance, forward_current) =
+            junction(voltage, self.saturation_current, self.emission_coefficient);
+
+        let (reverse_conductance, reverse_current) = junction(
+            -voltage - self.breakdown_voltage,
+            nalgebra::convert(ZENER_BREAKDOWN_CURRENT),
+            self.emission_coefficient,
+        );
+
+        Some(with_min_conductance(
+            voltage,
+            forward_conductance + reverse_conductance,
+            forward_current - reverse_current - self.saturation_current,
+        ))
+    }
+
+    fn limit(&self, voltage: T, previous: T) -> T {
+        // the junction closest to conducting is limited
+        if voltage + voltage > -self.breakdown_voltage {
+            limit_junction(
+                voltage,
+                previous,
+                self.saturation_current,
+                self.emission_coefficient,
+            )
+        } else {
+            let reverse = limit_junction(
+                -voltage - self.breakdown_voltage,
+                -previous - self.breakdown_voltage,
+                nalgebra::convert(ZENER_BREAKDOWN_CURRENT),
+                self.emission_coefficient,
+            );
+
+            -reverse - self.breakdown_voltage
+        }
+    }
+}
+
+pub const ZENER_BREAKDOWN_CURRENT: f64 = 1e-3;
+
+impl<T> ZenerDiode<T> {
+    pub fn new(saturation_current: T, emission_coefficient: T, breakdown_voltage: T) -> Self {
+        Self {
+            saturation_current,
+            emission_coefficient,
+            breakdown_voltage,
+        }
+    }
+}
+
+pub struct IdealCurrentSource<T = f32> {
+    pub amperage: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for IdealCurrentSource<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn amperage(&self) -> Option<T> {
+        Some(self.amperage)
+    }
+}
+
+impl<T> IdealCurrentSource<T> {
+    pub fn new(amperage: T) -> Self {
+        Self { amperage }
+    }
+}
+
+/// Voltage controlled voltage source with the terminals `[out+, out-, control+, control-]`
+/// added with [`Circuit::add_multi`](crate::Circuit::add_multi):
+/// `φ(out+) - φ(out-) = gain * (φ(control+) - φ(control-))`.
+pub struct Vcvs<T = f32> {
+    pub gain: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Vcvs<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        let k = stamp.controlled_voltage([0, 1], Unknown::Potential(2), self.gain);
+
+        stamp.entry(Unknown::Current(k), Unknown::Potential(3), self.gain);
+    }
+}
+
+impl<T> Vcvs<T> {
+    pub fn new(gain: T) -> Self {
+        Self { gain }
+    }
+}
+
+/// Voltage controlled current source with the terminals `[out+, out-, control+, control-]`
+/// added with [`Circuit::add_multi`](crate::Circuit::add_multi): the current
+/// `transconductance * (φ(control+) - φ(control-))` flows from `out+` to `out-` through it.
+pub struct Vccs<T = f32> {
+    pub transconductance: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Vccs<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        stamp.transconductance([0, 1], [2, 3], self.transconductance);
+    }
+}
+
+impl<T> Vccs<T> {
+    pub fn new(transconductance: T) -> Self {
+        Self { transconductance }
+    }
+}
+
+/// Current controlled voltage source with the terminals `[out+, out-]` added with
+/// [`Circuit::add_multi`](crate::Circuit::add_multi): `φ(out+) - φ(out-) = transresistance * I`,
+/// where `I` is the current of the `control` element. Without it the voltage is zero.
+pub struct Ccvs<T = f32> {
+    pub transresistance: T,
+    pub control: Option<ElementId>,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Ccvs<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn control(&self) -> Option<ElementId> {
+        self.control
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        match self.control {
+            Some(id) => {
+                let control = Unknown::ElementCurrent(id);
+
+                stamp.controlled_voltage([0, 1], control, self.transresistance);
+            }
+            None => {
+                stamp.voltage_source([0, 1], T::zero());
+            }
+        }
+    }
+}
+
+impl<T> Ccvs<T> {
+    pub fn new(transresistance: T, control: Option<ElementId>) -> Self {
+        Self {
+            transresistance,
+            control,
+        }
+    }
+}
+
+/// Current controlled current source with the terminals `[out+, out-]` added with
+/// [`Circuit::add_multi`](crate::Circuit::add_multi): the current `gain * I` flows from `out+`
+/// to `out-` through it, where `I` is the current of the `control` element.
+/// Without it there is no current.
+pub struct Cccs<T = f32> {
+    pub gain: T,
+    pub control: Option<ElementId>,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Cccs<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn control(&self) -> Option<ElementId> {
+        self.control
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        if let Some(id) = self.control {
+            stamp.controlled_current([0, 1], Unknown::ElementCurrent(id), self.gain);
+        }
+    }
+}
+
+impl<T> Cccs<T> {
+    pub fn new(gain: T, control: Option<ElementId>) -> Self {
+        Self { gain, control }
+    }
+}
+
+/// Ideal operational amplifier with the terminals `[in+, in-, out]` added with
+/// [`Circuit::add_multi`](crate::Circuit::add_multi): the output takes any voltage keeping
+/// the inputs at the same potential, so it needs a negative feedback. The output current
+/// returns to the ground, see [`Circuit::set_ground`](crate::Circuit::set_ground).
+pub struct IdealOpAmp;
+
+impl<T: RealField + Copy> Conductor<T> for IdealOpAmp {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        use Unknown::{Current, Potential};
+
+        let k = stamp.add_current();
+
+        stamp.set_grounded(2);
+        stamp.entry(Potential(2), Current(k), T::one());
+        stamp.entry(Current(k), Potential(0), T::one());
+        stamp.entry(Current(k), Potential(1), -T::one());
+    }
+}
+
+/// Operational amplifier with the terminals `[in+, in-, out]` added with
+/// [`Circuit::add_multi`](crate::Circuit::add_multi): the output voltage relative to the ground
+/// is `gain * (φ(in+) - φ(in-))` around the middle of the rails and saturates smoothly at them.
+/// The output current returns to the ground like for [`IdealOpAmp`].
+pub struct OpAmp<T = f32> {
+    pub gain: T,
+    pub positive_rail: T,
+    pub negative_rail: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for OpAmp<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        use Unknown::{Current, Potential};
+
+        let two = T::one() + T::one();
+
+        let middle = (self.positive_rail + self.negative_rail) / two;
+        let swing = (self.positive_rail - self.negative_rail) / two;
+
+        // `middle + swing * tanh(gain * voltage / swing)` linearized at the previous voltage
+        let voltage = stamp.voltage([0, 1]);
+        let tanh = (self.gain * voltage / swing).tanh();
+
+        let output = middle + swing * tanh;
+        let slope = self.gain * (T::one() - tanh * tanh);
+
+        let k = stamp.add_current();
+
+        stamp.set_grounded(2);
+        stamp.entry(Potential(2), Current(k), T::one());
+        stamp.entry(Current(k), Potential(2), T::one());
+        stamp.entry(Current(k), Potential(0), -slope);
+        stamp.entry(Current(k), Potential(1), slope);
+        stamp.add_rhs(Current(k), output - slope * voltage);
+    }
+}
+
+impl<T> OpAmp<T> {
+    pub fn new(gain: T, positive_rail: T, negative_rail: T) -> Self {
+        Self {
+            gain,
+            positive_rail,
+            negative_rail,
+        }
+    }
+}
+
+/// `N` stands for NPN transistors and N-channel MOSFETs, the voltages and currents
+/// of `P` types are reversed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Polarity {
+    N,
+    P,
+}
+
+impl Polarity {
+    fn sign<T: RealField + Copy>(self) -> T {
+        match self {
+            Polarity::N => T::one(),
+            Polarity::P => -T::one(),
+        }
+    }
+}
+
+/// Ebers–Moll bipolar transistor with the terminals `[collector, base, emitter]` added with
+/// [`Circuit::add_multi`](crate::Circuit::add_multi), `beta` is its forward current gain.
+pub struct Bjt<T = f32> {
+    pub polarity: Polarity,
+    pub beta: T,
+    pub saturation_current: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Bjt<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        let sign = self.polarity.sign::<T>();
+
+        let pairs = [[1, 2], [1, 0]];
+        let voltages = pairs.map(|terminals| sign * stamp.voltage(terminals));
+
+        // currents of the base-emitter and the base-collector junctions
+        let [(forward_conductance, forward), (reverse_conductance, reverse)] =
+            voltages.map(|voltage| {
+                let (conductance, current) = junction(voltage, self.saturation_current, T::one());
+
+                with_min_conductance(voltage, conductance, current - self.saturation_current)
+            });
+
+        let reverse_beta = nalgebra::convert::<_, T>(REVERSE_BETA);
+
+        // the transport current flows from the collector to the emitter
+        let collector = forward - reverse - reverse / reverse_beta;
+        let collector_conductances = [
+            forward_conductance,
+            -reverse_conductance - reverse_conductance / reverse_beta,
+        ];
+
+        let base = forward / self.beta + reverse / reverse_beta;
+        let base_conductances = [
+            forward_conductance / self.beta,
+            reverse_conductance / reverse_beta,
+        ];
+
+        let linearized = Linearized {
+            sign,
+            pairs,
+            voltages,
+        };
+
+        linearized.stamp(stamp, [0, 2], collector, collector_conductances);
+        linearized.stamp(stamp, [1, 2], base, base_conductances);
+    }
+
+    fn limit_potentials(&self, potentials: &mut [T], previous: &[T]) {
+        let sign = self.polarity.sign::<T>();
+
+        // the base is kept and the collector and the emitter follow the junction voltages
+        for terminal in [0, 2] {
+            let voltage = sign * (potentials[1] - potentials[terminal]);
+            let previous = sign * (previous[1] - previous[terminal]);
+
+            let voltage = limit_junction(voltage, previous, self.saturation_current, T::one());
+
+            potentials[terminal] = potentials[1] - sign * voltage;
+        }
+    }
+}
+
+impl<T> Bjt<T> {
+    pub fn new(polarity: Polarity, beta: T, saturation_current: T) -> Self {
+        Self {
+            polarity,
+            beta,
+            saturation_current,
+        }
+    }
+}
+
+/// Square-law MOSFET with the terminals `[drain, gate, source]` added with
+/// [`Circuit::add_multi`](crate::Circuit::add_multi): the saturated drain current is
+/// `transconductance / 2 * (V_GS - threshold_voltage)^2`. The drain and the source swap
+/// when the drain voltage is reversed.
+pub struct Mosfet<T = f32> {
+    pub polarity: Polarity,
+    pub threshold_voltage: T,
+    pub transconductance: T,
+}
+
+impl<T: RealField + Copy> Conductor<T> for Mosfet<T> {
+    fn emf(&self) -> T {
+        T::zero()
+    }
+
+    fn resistance(&self) -> T {
+        T::zero()
+    }
+
+    fn stamp(&self, stamp: &mut Stamp<T>) {
+        let sign = self.polarity.sign::<T>();
+
+        let pairs = [[1, 2], [0, 2]];
+        let voltages = pairs.map(|terminals| sign * stamp.voltage(terminals));
+        let [gate, drain] = voltages;
+
+        let (current, conductances) = if drain >= T::zero() {
+            self.drain_current(gate, drain)
+        } else {
+            // `I(V_GS, V_DS) = -I(V_GS - V_DS, -V_DS)`
+            let (current, [gate_conductance, drain_conductance]) =
+                self.drain_current(gate - drain, -drain);
+
+            (
+                -current,
+                [-gate_conductance, gate_conductance + drain_conductance],
+            )
+        };
+
+        let (drain_conductance, current) = with_min_conductance(drain, conductances[1], current);
+
+        let linearized = Linearized {
+            sign,
+            pairs,
+            voltages,
+        };
+
+        linearized.stamp(stamp, [0, 2], current, [conductances[0], drain_conductance]);
+    }
+}
+
+impl<T: RealField + Copy> Mosfet<T> {
+    pub fn new(polarity: Polarity, threshold_voltage: T, transconductance: T) -> Self {
+        Self {
+            polarity,
+            threshold_voltage,
+            transconductance,
+        }
+    }
+
+    /// The current from the drain to the source and its derivatives by `V_GS` and `V_DS`
+    /// for a non-negative `V_DS`.
+    fn drain_current(&self, gate: T, drain: T) -> (T, [T; 2]) {
+        let overdrive = gate - self.threshold_voltage;
+        let k = self.transconductance;
+
+        if overdrive <= T::zero() {
+            (T::zero(), [T::zero(); 2])
+        } else if drain < overdrive {
+            let current = k * (overdrive - drain / (T::one() + T::one())) * drain;
+
+            (current, [k * drain, k * (overdrive - drain)])
+        } else {
+            let current = k * overdrive * overdrive / (T::one() + T::one());
+
+            (current, [k * overdrive, T::zero()])
+        }
+    }
+}
+
+/// The voltages a transistor is linearized at, they are taken between the terminal `pairs`
+/// and multiplied by `sign` like the currents.
+struct Linearized<T> {
+    sign: T,
+    pairs: [[usize; 2]; 2],
+    voltages: [T; 2],
+}
+
+impl<T: RealField + Copy> Linearized<T> {
+    /// Stamps `current` flowing from the terminal `a` to the terminal `b` with its
+    /// `conductances`, the derivatives by the voltages.
+    fn stamp(&self, stamp: &mut Stamp<T>, [a, b]: [usize; 2], current: T, conductances: [T; 2]) {
+        let mut constant = self.sign * current;
+
+        for (i, conductance) in conductances.into_iter().enumerate() {
+            stamp.transconductance([a, b], self.pairs[i], conductance);
+
+            constant -= conductance * self.sign * self.voltages[i];
+        }
+
+        stamp.current([a, b], constant);
+    }
+}
+
+const THERMAL_VOLTAGE: f64 = 0.025852;
+/// Keeps reverse biased junctions from breaking the circuit.
+const MIN_CONDUCTANCE: f64 = 1e-12;
+/// Exponents above it are continued linearly to not overflow.
+const MAX_EXPONENT: f64 = 80.0;
+/// Current gain of bipolar transistors with the collector and the emitter swapped.
+const REVERSE_BETA: f64 = 1.0;
+
+/// Conductance and current of `saturation_current * exp(voltage / (emission_coefficient * V_T))`.
+fn junction<T: RealField + Copy>(
+    voltage: T,
+    saturation_current: T,
+    emission_coefficient: T,
+) -> (T, T) {
+    let thermal_voltage = emission_coefficient * nalgebra::convert(THERMAL_VOLTAGE);
+    let exponent = voltage / thermal_voltage;
+    let max_exponent = nalgebra::convert(MAX_EXPONENT);
+
+    let current = saturation_current * exponent.min(max_exponent).exp();
+    let conductance = current / thermal_voltage;
+
+    let excess = (exponent - max_exponent).max(T::zero()) * thermal_voltage;
+
+    (conductance, current + conductance * excess)
+}
+
+/// Limits the voltage of a junction above its critical voltage to the logarithm
+/// of the change, which is how fast the exponent can be followed.
+fn limit_junction<T: RealField + Copy>(
+    voltage: T,
+    previous: T,
+    saturation_current: T,
+    emission_coefficient: T,
+) -> T {
+    let thermal_voltage = emission_coefficient * nalgebra::convert(THERMAL_VOLTAGE);
+    let critical = thermal_voltage
+        * (thermal_voltage / (saturation_current * (T::one() + T::one()).sqrt())).ln();
+
+    if voltage <= critical || (voltage - previous).abs() <= thermal_voltage + thermal_voltage {
+        return voltage;
+    }
+
+    if previous > T::zero() {
+        let argument = T::one() + (voltage - previous) / thermal_voltage;
+
+        if argument > T::zero() {
+            previous + thermal_voltage * argument.ln()
+        } else {
+            critical
+        }
+    } else {
+        thermal_voltage * (voltage / thermal_voltage).ln()
+    }
+}
+
+fn with_min_conductance<T: RealField + Copy>(voltage: T, conductance: T, current: T) -> (T, T) {
+    let min_conductance = nalgebra::convert::<_, T>(MIN_CONDUCTANCE);
+
+    (
+        conductance + min_conductance,
+        current + min_conductance * voltage,
+    )
+}